@@ -12,7 +12,7 @@ pub struct SellResult {
     pub sol_amount: u64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AMM {
     pub virtual_sol_reserves: u128,
     pub virtual_token_reserves: u128,
@@ -51,6 +51,30 @@ impl AMM {
         Some(amount_needed)
     }
 
+    /// Inverse of `get_buy_price`: the most tokens that can be bought with `sol_amount`
+    /// once the fee on the purchase price is added on top. Never exceeds the real token reserves.
+    pub fn get_tokens_for_sol(&self, sol_amount: u128, fee_basis_points: u128) -> Option<u128> {
+        let fee = |amount: u128| amount.checked_mul(fee_basis_points).map(|scaled| scaled / 10000);
+
+        //largest purchase price whose price plus fee fits in sol_amount
+        let mut sol_for_tokens = sol_amount.checked_mul(10000)?.checked_div(fee_basis_points.checked_add(10000)?)?;
+        while sol_for_tokens < sol_amount && sol_for_tokens + 1 + fee(sol_for_tokens + 1)? <= sol_amount {
+            sol_for_tokens += 1;
+        }
+
+        let product_of_reserves = self.virtual_sol_reserves.checked_mul(self.virtual_token_reserves)?;
+        let new_virtual_sol_reserves = self.virtual_sol_reserves.checked_add(sol_for_tokens)?;
+        let min_virtual_token_reserves = product_of_reserves.checked_div(new_virtual_sol_reserves)?.checked_add(1)?;
+        let tokens = self.virtual_token_reserves.checked_sub(min_virtual_token_reserves)?;
+
+        let final_tokens = tokens.min(self.real_token_reserves);
+        if final_tokens == 0 {
+            return None;
+        }
+
+        Some(final_tokens)
+    }
+
     pub fn apply_buy(&mut self, token_amount: u128) -> Option<BuyResult> {
         let final_token_amount = if token_amount > self.real_token_reserves {
             self.real_token_reserves
@@ -87,6 +111,45 @@ impl AMM {
         })
     }
 
+    /// Inverse of `apply_sell`: the fewest tokens that have to be sold for the SOL output,
+    /// after the fee on it is taken out, to be at least `sol_amount`.
+    pub fn get_tokens_for_sell(&self, sol_amount: u128, fee_basis_points: u128) -> Option<u128> {
+        if sol_amount == 0 || fee_basis_points >= 10000 {
+            return None;
+        }
+
+        let fee = |amount: u128| amount.checked_mul(fee_basis_points).map(|scaled| scaled / 10000);
+
+        //smallest sell output that is still sol_amount once the fee is taken out
+        let fee_complement = 10000 - fee_basis_points;
+        let mut sol_before_fee = sol_amount.checked_mul(10000)?.checked_add(fee_complement - 1)?.checked_div(fee_complement)?;
+        while sol_before_fee > sol_amount && sol_before_fee - 1 - fee(sol_before_fee - 1)? >= sol_amount {
+            sol_before_fee -= 1;
+        }
+
+        if sol_before_fee > self.real_sol_reserves {
+            return None;
+        }
+
+        //apply_sell pays virtual_sol_reserves * floor(tokens * scaling_factor / (virtual_token_reserves + tokens)) / scaling_factor
+        let scaling_factor = self.initial_virtual_token_reserves;
+        let min_token_sell_proportion = sol_before_fee
+            .checked_mul(scaling_factor)?
+            .checked_add(self.virtual_sol_reserves.checked_sub(1)?)?
+            .checked_div(self.virtual_sol_reserves)?;
+        let remaining_proportion = scaling_factor.checked_sub(min_token_sell_proportion)?;
+        if remaining_proportion == 0 {
+            return None;
+        }
+
+        let tokens = min_token_sell_proportion
+            .checked_mul(self.virtual_token_reserves)?
+            .checked_add(remaining_proportion - 1)?
+            .checked_div(remaining_proportion)?;
+
+        Some(tokens)
+    }
+
     pub fn get_sell_price(&self, tokens: u128) -> Option<u128> {
         if tokens <= 0 || tokens > self.virtual_token_reserves {
             return None;
@@ -166,6 +229,46 @@ mod tests {
         assert_eq!(amm.real_sol_reserves, 410);    
     }
 
+    #[test]
+    fn test_get_tokens_for_sell() {
+        let amm = AMM::new(1000, 1000, 500, 500, 1000);
+
+        // Edge case: nothing to receive
+        assert_eq!(amm.get_tokens_for_sell(0, 0), None);
+
+        // Inverse of apply_sell
+        assert_eq!(amm.get_tokens_for_sell(90, 0), Some(99));
+        assert_eq!(AMM::new(1000, 1000, 500, 500, 1000).apply_sell(99).unwrap().sol_amount, 90);
+        assert_eq!(AMM::new(1000, 1000, 500, 500, 1000).apply_sell(98).unwrap().sol_amount, 89);
+
+        // Fee is taken out of the output
+        assert_eq!(amm.get_tokens_for_sell(90, 1000), Some(110));
+        assert_eq!(AMM::new(1000, 1000, 500, 500, 1000).apply_sell(110).unwrap().sol_amount, 99);
+
+        // Can't receive more than the real sol reserves
+        assert_eq!(amm.get_tokens_for_sell(501, 0), None);
+    }
+
+    #[test]
+    fn test_get_tokens_for_sell_is_minimal() {
+        let (virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves) =
+            (40_000_000_000, 800_000_000_000_000, 10_000_000_000, 520_000_000_000_000);
+        let initial_virtual_token_reserves = 1_073_000_000_000_000;
+        let new_amm = || AMM::new(virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves, initial_virtual_token_reserves);
+
+        for sol_amount in [1_000_000u128, 123_456_789, 1_500_000_000, 9_000_000_000] {
+            for fee_basis_points in [0u128, 50, 100, 1000] {
+                let tokens = new_amm().get_tokens_for_sell(sol_amount, fee_basis_points).unwrap();
+
+                let output = new_amm().apply_sell(tokens).unwrap().sol_amount as u128;
+                assert!(output - output * fee_basis_points / 10000 >= sol_amount);
+
+                let smaller_output = new_amm().apply_sell(tokens - 1).unwrap().sol_amount as u128;
+                assert!(smaller_output - smaller_output * fee_basis_points / 10000 < sol_amount);
+            }
+        }
+    }
+
     #[test]
     fn test_get_sell_price() {
         let amm = AMM::new(1000, 1000, 500, 500, 1000);
@@ -208,6 +311,43 @@ mod tests {
         assert_eq!(amm.real_sol_reserves, 621);
     }
 
+    #[test]
+    fn test_get_tokens_for_sol() {
+        let amm = AMM::new(1000, 1000, 500, 500, 1000);
+
+        // Edge case: nothing to spend
+        assert_eq!(amm.get_tokens_for_sol(0, 0), None);
+        assert_eq!(amm.get_tokens_for_sol(1, 0), None);
+
+        // Inverse of get_buy_price
+        assert_eq!(amm.get_tokens_for_sol(112, 0), Some(100));
+        assert_eq!(amm.get_tokens_for_sol(111, 0), Some(99));
+        assert_eq!(amm.get_buy_price(99), Some(110));
+
+        // Fee is paid on top of the purchase price
+        assert_eq!(amm.get_tokens_for_sol(113, 100), Some(100));
+        assert_eq!(amm.get_tokens_for_sol(112, 100), Some(99));
+
+        // Clamped at real token reserves
+        assert_eq!(amm.get_tokens_for_sol(1_000_000, 0), Some(500));
+    }
+
+    #[test]
+    fn test_get_tokens_for_sol_never_overspends() {
+        let amm = AMM::new(30_000_000_000, 1_073_000_000_000_000, 0, 793_100_000_000_000, 1_073_000_000_000_000);
+
+        for sol_amount in [1_000_000u128, 123_456_789, 1_500_000_000, 10_000_000_000] {
+            for fee_basis_points in [0u128, 50, 100, 1000] {
+                let tokens = amm.get_tokens_for_sol(sol_amount, fee_basis_points).unwrap();
+                let price = amm.get_buy_price(tokens).unwrap();
+                assert!(price + price * fee_basis_points / 10000 <= sol_amount);
+
+                let next_price = amm.get_buy_price(tokens + 1).unwrap();
+                assert!(next_price + next_price * fee_basis_points / 10000 > sol_amount);
+            }
+        }
+    }
+
     #[test]
     fn test_get_buy_price() {
         let amm = AMM::new(1000, 1000, 500, 500, 1000);
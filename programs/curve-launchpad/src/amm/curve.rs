@@ -0,0 +1,695 @@
+//! Curve shapes a bonding curve can be launched with. `ConstantProduct` is the original `AMM`,
+//! `Linear` grows the price by the launch price for every `initial_virtual_token_reserves` tokens sold.
+
+use std::fmt;
+
+use crate::{amm::{mul_div_ceil, mul_div_floor, BuyResult, SellResult, AMM}, state::CurveKind};
+
+const ONE_X64: u128 = 1 << 64;
+
+/// Exponential: the price grows by `EXPONENTIAL_GROWTH_BPS` every `initial_virtual_token_reserves / EXPONENTIAL_STEPS` tokens sold.
+pub const EXPONENTIAL_STEPS: u128 = 100;
+pub const EXPONENTIAL_GROWTH_BPS: u128 = 400;
+/// Stepped: the price grows by `STEPPED_TIER_INCREMENT_BPS` of the launch price every `initial_virtual_token_reserves / STEPPED_TIERS` tokens sold.
+pub const STEPPED_TIERS: u128 = 10;
+pub const STEPPED_TIER_INCREMENT_BPS: u128 = 10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveReserves {
+    pub virtual_sol_reserves: u128,
+    pub virtual_token_reserves: u128,
+    pub real_sol_reserves: u128,
+    pub real_token_reserves: u128,
+}
+
+/// A bonding curve pricing tokens in lamports.
+pub trait Curve: fmt::Debug {
+    /// SOL needed to buy `tokens`.
+    fn quote_buy(&self, tokens: u128) -> Option<u128>;
+
+    /// SOL received for selling `tokens`, as `apply_sell` would pay it.
+    fn quote_sell(&self, tokens: u128) -> Option<u128>;
+
+    fn apply_buy(&mut self, token_amount: u128) -> Option<BuyResult>;
+
+    fn apply_sell(&mut self, token_amount: u128) -> Option<SellResult>;
+
+    /// Marginal price in lamports per token, as a Q64.64 fixed point number.
+    fn spot_price(&self) -> Option<u128>;
+
+    /// `spot_price` once `tokens` are bought.
+    fn spot_price_after_buy(&self, tokens: u128) -> Option<u128>;
+
+    fn reserves(&self) -> CurveReserves;
+
+    /// Most tokens `sol_amount` buys once the fee is added on top, clamped at the real token reserves.
+    fn get_tokens_for_sol(&self, sol_amount: u128, fee_basis_points: u128) -> Option<u128>;
+
+    /// Fewest tokens to sell for the output net of the fee to be at least `sol_amount`.
+    fn get_tokens_for_sell(&self, sol_amount: u128, fee_basis_points: u128) -> Option<u128>;
+
+    /// Fewest tokens, at most `max_tokens`, to buy for the real SOL reserves to reach `target`,
+    /// None if buying `max_tokens` or the real token reserves falls short.
+    fn tokens_to_reach_real_sol(&self, target: u128, max_tokens: u128) -> Option<u128> {
+        let needed = target.saturating_sub(self.reserves().real_sol_reserves);
+        smallest_buy(max_tokens.min(self.reserves().real_token_reserves), |tokens| {
+            self.quote_buy(tokens).is_some_and(|cost| cost >= needed)
+        })
+    }
+
+    /// Fewest tokens, at most `max_tokens`, to buy for the spot price to reach `target`,
+    /// None if buying `max_tokens` or the real token reserves falls short.
+    fn tokens_to_reach_price(&self, target: u128, max_tokens: u128) -> Option<u128> {
+        smallest_buy(max_tokens.min(self.reserves().real_token_reserves), |tokens| {
+            self.spot_price_after_buy(tokens).is_some_and(|price| price >= target)
+        })
+    }
+}
+
+//smallest buy in [1, max_tokens] that is enough, only searched when `max_tokens` is, `enough` grows with the amount
+fn smallest_buy(max_tokens: u128, enough: impl Fn(u128) -> bool) -> Option<u128> {
+    if max_tokens == 0 || !enough(max_tokens) {
+        return None;
+    }
+
+    let (mut low, mut high) = (1u128, max_tokens);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if enough(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    Some(low)
+}
+
+pub fn new_curve(
+    curve_kind: CurveKind,
+    virtual_sol_reserves: u128,
+    virtual_token_reserves: u128,
+    real_sol_reserves: u128,
+    real_token_reserves: u128,
+    initial_virtual_token_reserves: u128,
+) -> Box<dyn Curve> {
+    match curve_kind {
+        CurveKind::ConstantProduct => Box::new(AMM::new(
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            real_sol_reserves,
+            real_token_reserves,
+            initial_virtual_token_reserves,
+        )),
+        CurveKind::Linear => Box::new(SupplyCurve::new(
+            Linear,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            real_sol_reserves,
+            real_token_reserves,
+            initial_virtual_token_reserves,
+        )),
+        CurveKind::Exponential => Box::new(SupplyCurve::new(
+            Exponential,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            real_sol_reserves,
+            real_token_reserves,
+            initial_virtual_token_reserves,
+        )),
+        CurveKind::Stepped => Box::new(SupplyCurve::new(
+            Stepped,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            real_sol_reserves,
+            real_token_reserves,
+            initial_virtual_token_reserves,
+        )),
+    }
+}
+
+impl Curve for AMM {
+    fn quote_buy(&self, tokens: u128) -> Option<u128> {
+        self.get_buy_price(tokens)
+    }
+
+    fn quote_sell(&self, tokens: u128) -> Option<u128> {
+        let mut amm = self.clone();
+        AMM::apply_sell(&mut amm, tokens).map(|result| result.sol_amount as u128)
+    }
+
+    fn apply_buy(&mut self, token_amount: u128) -> Option<BuyResult> {
+        AMM::apply_buy(self, token_amount)
+    }
+
+    fn apply_sell(&mut self, token_amount: u128) -> Option<SellResult> {
+        AMM::apply_sell(self, token_amount)
+    }
+
+    fn spot_price(&self) -> Option<u128> {
+        mul_div_floor(self.virtual_sol_reserves, ONE_X64, self.virtual_token_reserves)
+    }
+
+    fn spot_price_after_buy(&self, tokens: u128) -> Option<u128> {
+        if tokens == 0 {
+            return self.spot_price();
+        }
+
+        let virtual_sol_reserves = self.virtual_sol_reserves.checked_add(self.get_buy_price(tokens)?)?;
+        let virtual_token_reserves = self.virtual_token_reserves.checked_sub(tokens)?;
+        mul_div_floor(virtual_sol_reserves, ONE_X64, virtual_token_reserves)
+    }
+
+    fn reserves(&self) -> CurveReserves {
+        CurveReserves {
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+        }
+    }
+
+    fn get_tokens_for_sol(&self, sol_amount: u128, fee_basis_points: u128) -> Option<u128> {
+        AMM::get_tokens_for_sol(self, sol_amount, fee_basis_points)
+    }
+
+    fn get_tokens_for_sell(&self, sol_amount: u128, fee_basis_points: u128) -> Option<u128> {
+        AMM::get_tokens_for_sell(self, sol_amount, fee_basis_points)
+    }
+}
+
+/// How the price of a `SupplyCurve` grows with the number of tokens sold.
+pub trait PriceShape: fmt::Debug + Clone {
+    /// Price relative to the launch price once `sold` tokens are sold, as Q64.64.
+    fn multiplier(&self, sold: u128, initial_virtual_token_reserves: u128) -> Option<u128>;
+
+    /// Sum of the multiplier over the tokens in `[from, to)`, as Q64.64.
+    fn integral(&self, from: u128, to: u128, initial_virtual_token_reserves: u128) -> Option<u128>;
+}
+
+#[derive(Debug, Clone)]
+pub struct Linear;
+
+#[derive(Debug, Clone)]
+pub struct Exponential;
+
+#[derive(Debug, Clone)]
+pub struct Stepped;
+
+impl PriceShape for Linear {
+    fn multiplier(&self, sold: u128, initial_virtual_token_reserves: u128) -> Option<u128> {
+        ONE_X64.checked_add(mul_div_floor(sold, ONE_X64, initial_virtual_token_reserves)?)
+    }
+
+    fn integral(&self, from: u128, to: u128, initial_virtual_token_reserves: u128) -> Option<u128> {
+        //(to - from) + (to^2 - from^2) / (2 * initial_virtual_token_reserves)
+        let tokens = to.checked_sub(from)?;
+        let growth = mul_div_floor(
+            tokens.checked_mul(from.checked_add(to)?)?,
+            ONE_X64,
+            initial_virtual_token_reserves.checked_mul(2)?,
+        )?;
+        tokens.checked_mul(ONE_X64)?.checked_add(growth)
+    }
+}
+
+impl Exponential {
+    fn step(initial_virtual_token_reserves: u128) -> Option<u128> {
+        let step = initial_virtual_token_reserves / EXPONENTIAL_STEPS;
+        if step == 0 {
+            return None;
+        }
+        Some(step)
+    }
+
+    fn growth() -> u128 {
+        ONE_X64 / 10000 * (10000 + EXPONENTIAL_GROWTH_BPS)
+    }
+}
+
+impl PriceShape for Exponential {
+    fn multiplier(&self, sold: u128, initial_virtual_token_reserves: u128) -> Option<u128> {
+        pow_x64(Self::growth(), sold / Self::step(initial_virtual_token_reserves)?)
+    }
+
+    fn integral(&self, from: u128, to: u128, initial_virtual_token_reserves: u128) -> Option<u128> {
+        let step = Self::step(initial_virtual_token_reserves)?;
+        let (first_step, last_step) = (from / step, to / step);
+
+        if first_step == last_step {
+            return to.checked_sub(from)?.checked_mul(self.multiplier(from, initial_virtual_token_reserves)?);
+        }
+
+        //partial first and last steps
+        let first = (step * (first_step + 1) - from).checked_mul(pow_x64(Self::growth(), first_step)?)?;
+        let last = (to - step * last_step).checked_mul(pow_x64(Self::growth(), last_step)?)?;
+
+        //full steps in between, a geometric series
+        let full_steps = last_step - first_step - 1;
+        let series = mul_div_floor(
+            pow_x64(Self::growth(), full_steps)? - ONE_X64,
+            ONE_X64,
+            Self::growth() - ONE_X64,
+        )?;
+        let middle = step.checked_mul(mul_div_floor(pow_x64(Self::growth(), first_step + 1)?, series, ONE_X64)?)?;
+
+        first.checked_add(middle)?.checked_add(last)
+    }
+}
+
+impl Stepped {
+    fn tier(initial_virtual_token_reserves: u128) -> Option<u128> {
+        let tier = initial_virtual_token_reserves / STEPPED_TIERS;
+        if tier == 0 {
+            return None;
+        }
+        Some(tier)
+    }
+
+    fn tier_multiplier(tier_index: u128) -> Option<u128> {
+        ONE_X64.checked_add(mul_div_floor(tier_index.checked_mul(STEPPED_TIER_INCREMENT_BPS)?, ONE_X64, 10000)?)
+    }
+}
+
+impl PriceShape for Stepped {
+    fn multiplier(&self, sold: u128, initial_virtual_token_reserves: u128) -> Option<u128> {
+        Self::tier_multiplier(sold / Self::tier(initial_virtual_token_reserves)?)
+    }
+
+    fn integral(&self, from: u128, to: u128, initial_virtual_token_reserves: u128) -> Option<u128> {
+        let tier = Self::tier(initial_virtual_token_reserves)?;
+
+        let mut integral = 0u128;
+        let mut position = from;
+        while position < to {
+            let tier_index = position / tier;
+            let tier_end = (tier_index + 1).checked_mul(tier)?.min(to);
+            integral = integral.checked_add((tier_end - position).checked_mul(Self::tier_multiplier(tier_index)?)?)?;
+            position = tier_end;
+        }
+
+        Some(integral)
+    }
+}
+
+/// A curve that prices each token by how many tokens were sold before it.
+/// The launch price is `initial_virtual_sol_reserves / initial_virtual_token_reserves`, the same
+/// as a constant product curve with the same reserves, and `PriceShape` decides how it grows.
+#[derive(Debug, Clone)]
+pub struct SupplyCurve<S: PriceShape> {
+    pub shape: S,
+    pub virtual_sol_reserves: u128,
+    pub virtual_token_reserves: u128,
+    pub real_sol_reserves: u128,
+    pub real_token_reserves: u128,
+    pub initial_virtual_token_reserves: u128,
+}
+
+pub type LinearCurve = SupplyCurve<Linear>;
+pub type ExponentialCurve = SupplyCurve<Exponential>;
+pub type SteppedCurve = SupplyCurve<Stepped>;
+
+impl<S: PriceShape> SupplyCurve<S> {
+    pub fn new(
+        shape: S,
+        virtual_sol_reserves: u128,
+        virtual_token_reserves: u128,
+        real_sol_reserves: u128,
+        real_token_reserves: u128,
+        initial_virtual_token_reserves: u128,
+    ) -> Self {
+        SupplyCurve {
+            shape,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            real_sol_reserves,
+            real_token_reserves,
+            initial_virtual_token_reserves,
+        }
+    }
+
+    fn tokens_sold(&self) -> Option<u128> {
+        self.initial_virtual_token_reserves.checked_sub(self.virtual_token_reserves)
+    }
+
+    //virtual and real SOL reserves move together, so their difference is the initial virtual SOL reserves
+    fn initial_virtual_sol_reserves(&self) -> Option<u128> {
+        self.virtual_sol_reserves.checked_sub(self.real_sol_reserves)
+    }
+
+    fn cost(&self, from: u128, to: u128, round_up: bool) -> Option<u128> {
+        let integral = self.shape.integral(from, to, self.initial_virtual_token_reserves)?;
+        let denominator = self.initial_virtual_token_reserves.checked_mul(ONE_X64)?;
+
+        if round_up {
+            mul_div_ceil(self.initial_virtual_sol_reserves()?, integral, denominator)
+        } else {
+            mul_div_floor(self.initial_virtual_sol_reserves()?, integral, denominator)
+        }
+    }
+}
+
+impl<S: PriceShape> Curve for SupplyCurve<S> {
+    fn quote_buy(&self, tokens: u128) -> Option<u128> {
+        if tokens == 0 || tokens > self.real_token_reserves {
+            return None;
+        }
+
+        let sold = self.tokens_sold()?;
+        self.cost(sold, sold.checked_add(tokens)?, true)
+    }
+
+    fn quote_sell(&self, tokens: u128) -> Option<u128> {
+        let sold = self.tokens_sold()?;
+        if tokens == 0 || tokens > sold {
+            return None;
+        }
+
+        Some(self.cost(sold - tokens, sold, false)?.min(self.real_sol_reserves))
+    }
+
+    fn apply_buy(&mut self, token_amount: u128) -> Option<BuyResult> {
+        let final_token_amount = token_amount.min(self.real_token_reserves);
+        let sol_amount = self.quote_buy(final_token_amount)?;
+
+        self.virtual_token_reserves = self.virtual_token_reserves.checked_sub(final_token_amount)?;
+        self.real_token_reserves = self.real_token_reserves.checked_sub(final_token_amount)?;
+
+        self.virtual_sol_reserves = self.virtual_sol_reserves.checked_add(sol_amount)?;
+        self.real_sol_reserves = self.real_sol_reserves.checked_add(sol_amount)?;
+
+        Some(BuyResult {
+            token_amount: final_token_amount as u64,
+            sol_amount: sol_amount as u64,
+        })
+    }
+
+    fn apply_sell(&mut self, token_amount: u128) -> Option<SellResult> {
+        let sol_amount = self.quote_sell(token_amount)?;
+
+        self.virtual_token_reserves = self.virtual_token_reserves.checked_add(token_amount)?;
+        self.real_token_reserves = self.real_token_reserves.checked_add(token_amount)?;
+
+        self.virtual_sol_reserves = self.virtual_sol_reserves.checked_sub(sol_amount)?;
+        self.real_sol_reserves = self.real_sol_reserves.checked_sub(sol_amount)?;
+
+        Some(SellResult {
+            token_amount: token_amount as u64,
+            sol_amount: sol_amount as u64,
+        })
+    }
+
+    fn spot_price(&self) -> Option<u128> {
+        self.spot_price_after_buy(0)
+    }
+
+    fn spot_price_after_buy(&self, tokens: u128) -> Option<u128> {
+        if tokens > self.real_token_reserves {
+            return None;
+        }
+
+        let sold = self.tokens_sold()?.checked_add(tokens)?;
+        let multiplier = self.shape.multiplier(sold, self.initial_virtual_token_reserves)?;
+        mul_div_floor(self.initial_virtual_sol_reserves()?, multiplier, self.initial_virtual_token_reserves)
+    }
+
+    fn reserves(&self) -> CurveReserves {
+        CurveReserves {
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+        }
+    }
+
+    fn get_tokens_for_sol(&self, sol_amount: u128, fee_basis_points: u128) -> Option<u128> {
+        let affordable = |tokens: u128| {
+            self.quote_buy(tokens)
+                .and_then(|price| price.checked_add(price.checked_mul(fee_basis_points)? / 10000))
+                .is_some_and(|cost| cost <= sol_amount)
+        };
+
+        //largest affordable amount, the cost grows with the amount
+        let (mut low, mut high) = (0u128, self.real_token_reserves);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if affordable(mid) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        if low == 0 {
+            return None;
+        }
+        Some(low)
+    }
+
+    fn get_tokens_for_sell(&self, sol_amount: u128, fee_basis_points: u128) -> Option<u128> {
+        if sol_amount == 0 {
+            return None;
+        }
+
+        let enough = |tokens: u128| {
+            self.quote_sell(tokens)
+                .and_then(|output| output.checked_sub(output.checked_mul(fee_basis_points)? / 10000))
+                .is_some_and(|output| output >= sol_amount)
+        };
+
+        //smallest amount that is enough, the output grows with the amount
+        let (mut low, mut high) = (1u128, self.tokens_sold()?);
+        if !enough(high) {
+            return None;
+        }
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if enough(mid) {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        Some(low)
+    }
+}
+
+fn pow_x64(base: u128, exponent: u128) -> Option<u128> {
+    let mut result = ONE_X64;
+    let mut base = base;
+    let mut exponent = exponent;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_div_floor(result, base, ONE_X64)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul_div_floor(base, base, ONE_X64)?;
+        }
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURVE_KINDS: [CurveKind; 4] = [
+        CurveKind::ConstantProduct,
+        CurveKind::Linear,
+        CurveKind::Exponential,
+        CurveKind::Stepped,
+    ];
+
+    fn launch_curve(curve_kind: CurveKind) -> Box<dyn Curve> {
+        new_curve(
+            curve_kind,
+            30_000_000_000,
+            1_073_000_000_000_000,
+            0,
+            793_100_000_000_000,
+            1_073_000_000_000_000,
+        )
+    }
+
+    #[test]
+    fn test_pow_x64() {
+        assert_eq!(pow_x64(2 * ONE_X64, 0), Some(ONE_X64));
+        assert_eq!(pow_x64(2 * ONE_X64, 10), Some(1024 * ONE_X64));
+        assert_eq!(pow_x64(ONE_X64 / 2, 3), Some(ONE_X64 / 8));
+    }
+
+    #[test]
+    fn test_launch_price_matches_constant_product() {
+        let constant_product_price = launch_curve(CurveKind::ConstantProduct).spot_price().unwrap();
+        for curve_kind in CURVE_KINDS {
+            assert_eq!(launch_curve(curve_kind).spot_price().unwrap(), constant_product_price);
+        }
+    }
+
+    #[test]
+    fn test_buy_and_sell_too_much() {
+        for curve_kind in CURVE_KINDS {
+            let mut curve = launch_curve(curve_kind);
+            let real_token_reserves = curve.reserves().real_token_reserves;
+
+            // Attempt to buy more tokens than available in reserves
+            let buy_result = curve.apply_buy(real_token_reserves * 2).unwrap();
+            assert_eq!(buy_result.token_amount as u128, real_token_reserves, "{:?}", curve_kind);
+            assert_eq!(curve.reserves().real_token_reserves, 0);
+            assert_eq!(curve.reserves().real_sol_reserves, buy_result.sol_amount as u128);
+
+            // Nothing left to buy
+            assert!(curve.apply_buy(1).is_none());
+
+            // Selling everything back can't pay out more than the real sol reserves
+            let sell_result = curve.apply_sell(real_token_reserves).unwrap();
+            assert!(sell_result.sol_amount <= buy_result.sol_amount, "{:?}", curve_kind);
+            assert_eq!(curve.reserves().real_sol_reserves, (buy_result.sol_amount - sell_result.sol_amount) as u128);
+            assert_eq!(curve.reserves().real_token_reserves, real_token_reserves);
+        }
+    }
+
+    #[test]
+    fn test_apply_buy() {
+        for curve_kind in CURVE_KINDS {
+            let mut curve = launch_curve(curve_kind);
+            let before = curve.reserves();
+            let spot_price_before = curve.spot_price().unwrap();
+
+            let quote = curve.quote_buy(10_000_000_000_000).unwrap();
+            let result = curve.apply_buy(10_000_000_000_000).unwrap();
+            let after = curve.reserves();
+
+            assert_eq!(result.token_amount, 10_000_000_000_000);
+            assert_eq!(result.sol_amount as u128, quote);
+            assert_eq!(after.virtual_token_reserves, before.virtual_token_reserves - 10_000_000_000_000);
+            assert_eq!(after.real_token_reserves, before.real_token_reserves - 10_000_000_000_000);
+            assert_eq!(after.virtual_sol_reserves, before.virtual_sol_reserves + quote);
+            assert_eq!(after.real_sol_reserves, before.real_sol_reserves + quote);
+            assert!(curve.spot_price().unwrap() >= spot_price_before, "{:?}", curve_kind);
+        }
+    }
+
+    #[test]
+    fn test_apply_sell() {
+        for curve_kind in CURVE_KINDS {
+            let mut curve = launch_curve(curve_kind);
+            let buy_result = curve.apply_buy(100_000_000_000_000).unwrap();
+            let before = curve.reserves();
+
+            let quote = curve.quote_sell(40_000_000_000_000).unwrap();
+            let result = curve.apply_sell(40_000_000_000_000).unwrap();
+            let after = curve.reserves();
+
+            assert_eq!(result.token_amount, 40_000_000_000_000);
+            assert_eq!(result.sol_amount as u128, quote);
+            assert!(result.sol_amount < buy_result.sol_amount);
+            assert_eq!(after.virtual_token_reserves, before.virtual_token_reserves + 40_000_000_000_000);
+            assert_eq!(after.real_token_reserves, before.real_token_reserves + 40_000_000_000_000);
+            assert_eq!(after.virtual_sol_reserves, before.virtual_sol_reserves - quote);
+            assert_eq!(after.real_sol_reserves, before.real_sol_reserves - quote);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_does_not_profit() {
+        for curve_kind in CURVE_KINDS {
+            for token_amount in [1u128, 1_000, 1_000_000_000, 500_000_000_000_000] {
+                let mut curve = launch_curve(curve_kind);
+                let buy_result = curve.apply_buy(token_amount).unwrap();
+                let sell_result = curve.apply_sell(token_amount).unwrap();
+                assert!(sell_result.sol_amount <= buy_result.sol_amount, "{:?} {}", curve_kind, token_amount);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quote_edge_cases() {
+        for curve_kind in CURVE_KINDS {
+            let curve = launch_curve(curve_kind);
+
+            // Edge case: zero tokens
+            assert_eq!(curve.quote_buy(0), None);
+            assert_eq!(curve.quote_sell(0), None);
+
+            // Edge case: very large token amount
+            assert_eq!(curve.quote_buy(u64::MAX as u128), None);
+        }
+    }
+
+    #[test]
+    fn test_get_tokens_for_sol() {
+        for curve_kind in CURVE_KINDS {
+            let curve = launch_curve(curve_kind);
+
+            assert_eq!(curve.get_tokens_for_sol(0, 50), None);
+
+            for sol_amount in [1_000_000u128, 1_500_000_000, 20_000_000_000] {
+                let tokens = curve.get_tokens_for_sol(sol_amount, 50).unwrap();
+                let price = curve.quote_buy(tokens).unwrap();
+                assert!(price + price * 50 / 10000 <= sol_amount, "{:?}", curve_kind);
+
+                let next_price = curve.quote_buy(tokens + 1).unwrap();
+                assert!(next_price + next_price * 50 / 10000 > sol_amount, "{:?}", curve_kind);
+            }
+
+            // Clamped at real token reserves
+            assert_eq!(curve.get_tokens_for_sol(u64::MAX as u128, 50), Some(793_100_000_000_000));
+        }
+    }
+
+    #[test]
+    fn test_get_tokens_for_sell() {
+        for curve_kind in CURVE_KINDS {
+            let mut curve = launch_curve(curve_kind);
+            curve.apply_buy(300_000_000_000_000).unwrap();
+
+            assert_eq!(curve.get_tokens_for_sell(0, 50), None);
+
+            for sol_amount in [1_000_000u128, 1_500_000_000] {
+                let tokens = curve.get_tokens_for_sell(sol_amount, 50).unwrap();
+                let output = curve.quote_sell(tokens).unwrap();
+                assert!(output - output * 50 / 10000 >= sol_amount, "{:?}", curve_kind);
+
+                let smaller_output = curve.quote_sell(tokens - 1).unwrap();
+                assert!(smaller_output - smaller_output * 50 / 10000 < sol_amount, "{:?}", curve_kind);
+            }
+
+            // Can't receive more than the real sol reserves
+            let real_sol_reserves = curve.reserves().real_sol_reserves;
+            assert_eq!(curve.get_tokens_for_sell(real_sol_reserves + 1, 0), None);
+        }
+    }
+
+    #[test]
+    fn test_tokens_to_reach() {
+        for curve_kind in CURVE_KINDS {
+            let curve = launch_curve(curve_kind);
+
+            let tokens = curve.tokens_to_reach_real_sol(20_000_000_000, u128::MAX).unwrap();
+            assert!(curve.quote_buy(tokens).unwrap() >= 20_000_000_000, "{:?}", curve_kind);
+            assert!(curve.quote_buy(tokens - 1).unwrap() < 20_000_000_000, "{:?}", curve_kind);
+
+            let mut bought = launch_curve(curve_kind);
+            bought.apply_buy(tokens).unwrap();
+            assert_eq!(bought.spot_price(), curve.spot_price_after_buy(tokens), "{:?}", curve_kind);
+
+            let target_price = curve.spot_price().unwrap() * 3 / 2;
+            let tokens = curve.tokens_to_reach_price(target_price, u128::MAX).unwrap();
+            assert!(curve.spot_price_after_buy(tokens).unwrap() >= target_price, "{:?}", curve_kind);
+            assert!(curve.spot_price_after_buy(tokens - 1).unwrap() < target_price, "{:?}", curve_kind);
+
+            //a buy of exactly the tokens needed reaches the target, one token less doesn't
+            assert_eq!(curve.tokens_to_reach_price(target_price, tokens), Some(tokens));
+            assert_eq!(curve.tokens_to_reach_price(target_price, tokens - 1), None);
+
+            //out of reach of the real token reserves
+            assert_eq!(curve.tokens_to_reach_real_sol(u64::MAX as u128, u128::MAX), None);
+            assert_eq!(curve.tokens_to_reach_price(u128::MAX, u128::MAX), None);
+        }
+    }
+}
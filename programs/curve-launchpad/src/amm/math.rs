@@ -0,0 +1,114 @@
+//! Fixed point helpers shared by the curves and the whirlpool migration.
+
+/// Computes `a * b / denominator` rounded down, using a 256 bit intermediate product.
+pub fn mul_div_floor(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    if let Some(product) = a.checked_mul(b) {
+        return Some(product / denominator);
+    }
+
+    let (hi, lo) = full_mul(a, b);
+    //the quotient would not fit in a u128
+    if hi >= denominator {
+        return None;
+    }
+
+    let mut remainder = hi;
+    let mut quotient = 0u128;
+    for i in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+
+    Some(quotient)
+}
+
+/// Computes `a * b / denominator` rounded up.
+pub fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    let quotient = mul_div_floor(a, b, denominator)?;
+    let (hi, lo) = full_mul(a, b);
+    let (product_hi, product_lo) = full_mul(quotient, denominator);
+    if hi == product_hi && lo == product_lo {
+        return Some(quotient);
+    }
+    quotient.checked_add(1)
+}
+
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    let mask = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & mask);
+    let (b_hi, b_lo) = (b >> 64, b & mask);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let cross = (lo_lo >> 64) + (hi_lo & mask) + (lo_hi & mask);
+    let lo = (lo_lo & mask) | (cross << 64);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+
+    (hi, lo)
+}
+
+pub fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+
+    let mut root = 0u128;
+    let mut remainder = value;
+    let mut bit = 1u128 << ((127 - value.leading_zeros()) & !1);
+    while bit != 0 {
+        if remainder >= root + bit {
+            remainder -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_floor() {
+        assert_eq!(mul_div_floor(10, 10, 3), Some(33));
+        assert_eq!(mul_div_floor(10, 10, 0), None);
+        assert_eq!(mul_div_floor(u128::MAX, 2, 4), Some(u128::MAX / 2));
+        assert_eq!(mul_div_floor(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div_floor(1 << 100, 1 << 100, 1 << 90), Some(1 << 110));
+        assert_eq!(mul_div_floor(u128::MAX, 3, 2), None); //overflow
+    }
+
+    #[test]
+    fn test_mul_div_ceil() {
+        assert_eq!(mul_div_ceil(10, 10, 3), Some(34));
+        assert_eq!(mul_div_ceil(10, 10, 5), Some(20));
+        assert_eq!(mul_div_ceil(10, 10, 0), None);
+        assert_eq!(mul_div_ceil(1 << 100, 1 << 100, 3), mul_div_floor(1 << 100, 1 << 100, 3).map(|q| q + 1));
+        assert_eq!(mul_div_ceil(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(1), 1);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(1 << 64), 1 << 32);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+    }
+}
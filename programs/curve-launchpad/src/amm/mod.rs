@@ -1,2 +1,6 @@
 pub mod amm;
-pub use amm::*;
\ No newline at end of file
+pub mod curve;
+pub mod math;
+pub use amm::*;
+pub use curve::*;
+pub use math::*;
\ No newline at end of file
@@ -0,0 +1,42 @@
+use crate::{state::Global, AcceptAuthorityEvent, CurveLaunchpadError};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    user: Signer<'info>,
+}
+
+pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let global = &mut ctx.accounts.global;
+
+    //confirm program is initialized
+    require!(
+        global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //confirm user is the pending authority
+    require!(
+        global.pending_authority == Some(*ctx.accounts.user.to_account_info().key),
+        CurveLaunchpadError::InvalidPendingAuthority
+    );
+
+    let previous_authority = global.authority;
+    global.authority = *ctx.accounts.user.to_account_info().key;
+    global.pending_authority = None;
+
+    emit_cpi!(AcceptAuthorityEvent {
+        previous_authority,
+        authority: global.authority,
+    });
+
+    Ok(())
+}
@@ -1,16 +1,11 @@
-use anchor_lang::{prelude::*, solana_program::system_instruction};
-use anchor_spl::{associated_token::AssociatedToken, memo::Memo, token_interface::{self as token, Mint, TokenAccount, TokenInterface, TransferChecked}};
-use whirlpool::state::{FeeTier, Position, TickArray, Whirlpool, WhirlpoolsConfig};
-use std::str::FromStr;
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::{
-    amm, calculate_fee, check_buy_sell, state::{BondingCurve, Global, LastWithdraw, UserTransferData}, CompleteEvent, CurveLaunchpadError, TradeEvent
+    check_buy_sell, check_max_wallet, fee_config, graduation_target, presale_leaf, quote_account, quote_accounts, transfer_fee, transfer_quote, trade_fees, verify_merkle_proof, TradeFees, transfer_checked_with_hook, state::{BondingCurve, CreatorVault, Global, LaunchConfig, ReferrerStats, UserDeposit, UserTransferData}, CompleteEvent, CurveLaunchpadError, TradeEvent
 };
 
 #[event_cpi]
 #[derive(Accounts)]
-#[instruction(start_tick_index: i32)]
-
-
 pub struct Buy<'info> {
     #[account(mut)]
    pub user: Signer<'info>,
@@ -29,7 +24,7 @@ pub struct Buy<'info> {
 
     #[account(
         mut,
-        seeds = [BondingCurve::SEED_PREFIX, bonding_curve.creator.as_ref()],
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
         bump,
     )]
     pub bonding_curve: Box<Account<'info, BondingCurve>>,
@@ -40,10 +35,32 @@ pub struct Buy<'info> {
     )]
     pub bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + CreatorVault::INIT_SPACE,
+        seeds = [CreatorVault::SEED_PREFIX, bonding_curve.key().as_ref()],
+        bump,
+    )]
+    pub creator_vault: Box<Account<'info, CreatorVault>>,
+
+    /// Optional referrer, its share of the SOL fee accrues in its stats until claimed
+    pub referrer: Option<SystemAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ReferrerStats::INIT_SPACE,
+        seeds = [ReferrerStats::SEED_PREFIX, referrer.as_ref().map(|referrer| referrer.key()).unwrap_or_default().as_ref()],
+        bump,
+    )]
+    pub referrer_stats: Option<Box<Account<'info, ReferrerStats>>>,
+
     #[account(
         mut,
-     //   associated_token::mint = mint,
-     //   associated_token::authority = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
     pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -60,258 +77,461 @@ pub struct Buy<'info> {
     )]
     pub user_transfer_data: Box<Account<'info, UserTransferData>>,
 
+    //required on soft-cap curves, so the buy can be refunded
     #[account(
         init_if_needed,
-        space = 8 + LastWithdraw::INIT_SPACE,
-        seeds = [LastWithdraw::SEED_PREFIX],
-        bump,
         payer = user,
+        space = 8 + UserDeposit::INIT_SPACE,
+        seeds = [UserDeposit::SEED_PREFIX, mint.key().as_ref(), user.key().as_ref()],
+        bump,
     )]
-    pub last_withdraw: Box<Account<'info, LastWithdraw>>,
-    #[account(address = Pubkey::from_str("J5T5RStZBW2ayuTp5dGCQMHsUApCReRbytDMRd4ZP2aR").unwrap())]
-    pub whirlpools_config: Box<Account<'info, WhirlpoolsConfig>>,
+    pub user_deposit: Option<Box<Account<'info, UserDeposit>>>,
 
-    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,
-    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,
+    //only for curves priced in an SPL quote mint
+    #[account(address = bonding_curve.quote_mint @ CurveLaunchpadError::InvalidQuoteAccount)]
+    pub quote_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
-    #[account(seeds = [b"token_badge", whirlpools_config.key().as_ref(), token_mint_a.key().as_ref()], bump)]
-    /// CHECK: checked in the handler
-    pub token_badge_a: UncheckedAccount<'info>,
-    #[account(seeds = [b"token_badge", whirlpools_config.key().as_ref(), token_mint_b.key().as_ref()], bump)]
-    /// CHECK: checked in the handler
-    pub token_badge_b: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = bonding_curve.quote_vault @ CurveLaunchpadError::InvalidQuoteAccount,
+    )]
+    pub quote_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(mut)]
-    pub funder: Signer<'info>,
-
-    #[account(init,
-      seeds = [
-        b"whirlpool".as_ref(),
-        whirlpools_config.key().as_ref(),
-        token_mint_a.key().as_ref(),
-        token_mint_b.key().as_ref(),
-        256_u16.to_le_bytes().as_ref()
-      ],
-      bump,
-      payer = funder,
-      space = Whirlpool::LEN)]
-    pub whirlpool: Box<Account<'info, Whirlpool>>,
-
-    #[account(init,
-      payer = funder,
-      token::token_program = token_program_a,
-      token::mint = token_mint_a,
-      token::authority = whirlpool)]
-    pub token_vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(init,
-      payer = funder,
-      token::token_program = token_program_b,
-      token::mint = token_mint_b,
-      token::authority = whirlpool)]
-    pub token_vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(has_one = whirlpools_config, constraint = fee_tier.tick_spacing == 256_u16)]
-    pub fee_tier: Account<'info, FeeTier>,
-
-    #[account(address = token_mint_a.to_account_info().owner.clone())]
-    pub token_program_a: Interface<'info, TokenInterface>,
-    #[account(address = token_mint_b.to_account_info().owner.clone())]
-    pub token_program_b: Interface<'info, TokenInterface>,
-    pub rent: Sysvar<'info, Rent>,
+    pub user_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    #[account(
-      init,
-      payer = funder,
-      seeds = [b"tick_array", whirlpool.key().as_ref(), start_tick_index.to_string().as_bytes()],
-      bump,
-      space = TickArray::LEN)]
-    pub tick_array: AccountLoader<'info, TickArray>,
-
-    /// CHECK: safe, the account that will be the owner of the position can be arbitrary
-    pub owner: UncheckedAccount<'info>,
-
-    #[account(init,
-      payer = funder,
-      space = Position::LEN,
-      seeds = [b"position".as_ref(), position_mint.key().as_ref()],
-      bump,
-    )]
-    pub position: Box<Account<'info, Position>>,
+    #[account(mut)]
+    pub fee_recipient_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    #[account(init,
-        payer = funder,
-        mint::authority = whirlpool,
-        mint::decimals = 0,
-    )]
-    pub position_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub creator_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    #[account(init,
-      payer = funder,
-      associated_token::mint = position_mint,
-      associated_token::authority = owner,
-    )]
-    pub position_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub referrer_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
+    pub quote_token_program: Option<Interface<'info, TokenInterface>>,
 
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    //only for curves created under a launch config
+    #[account(address = bonding_curve.launch_config @ CurveLaunchpadError::InvalidLaunchConfig)]
+    pub launch_config: Option<Box<Account<'info, LaunchConfig>>>,
+}
 
-    pub memo_program: Program<'info, Memo>,
+/// Allowlist entry of the buyer, required while the presale is active.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PresaleProof {
+    pub max_token_amount: u64,
+    pub proof: Vec<[u8; 32]>,
+}
 
-    pub position_authority: Signer<'info>,
+/// Quote mint accounts of a buy on a curve priced in an SPL quote mint.
+pub struct BuyQuoteAccounts<'a, 'info> {
+    pub quote_mint: &'a InterfaceAccount<'info, Mint>,
+    pub quote_vault: AccountInfo<'info>,
+    pub quote_token_program: AccountInfo<'info>,
+    pub user_quote_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub fee_recipient_quote_account: AccountInfo<'info>,
+    pub creator_quote_account: AccountInfo<'info>,
+    pub referrer_quote_account: Option<AccountInfo<'info>>,
+}
 
-    #[account(mut, constraint = token_owner_account_a.mint == whirlpool.token_mint_a)]
-    pub token_owner_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
-    #[account(mut, constraint = token_owner_account_b.mint == whirlpool.token_mint_b)]
-    pub token_owner_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+/// Accounts a buy checks and pays, shared by `buy` and the initial buy of `create`.
+pub struct BuyAccounts<'a, 'info> {
+    pub user: AccountInfo<'info>,
+    pub global: &'a Global,
+    pub launch_config: &'a Option<Box<Account<'info, LaunchConfig>>>,
+    pub fee_recipient: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub bonding_curve: &'a mut Account<'info, BondingCurve>,
+    pub bonding_curve_bump: u8,
+    pub bonding_curve_token_account: AccountInfo<'info>,
+    //tokens held by the bonding curve token account before the buy
+    pub bonding_curve_token_amount: u64,
+    pub creator_vault: AccountInfo<'info>,
+    pub referrer: Option<Pubkey>,
+    pub referrer_stats: Option<&'a mut Account<'info, ReferrerStats>>,
+    pub user_token_account: AccountInfo<'info>,
+    //tokens held by the user token account before the buy
+    pub user_token_amount: u64,
+    pub user_transfer_data: &'a mut UserTransferData,
+    pub user_deposit: Option<&'a mut Account<'info, UserDeposit>>,
+    pub quote: Option<BuyQuoteAccounts<'a, 'info>>,
+    pub system_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub remaining_accounts: &'a [AccountInfo<'info>],
+}
 
-    #[account(mut, has_one = whirlpool)]
-    pub tick_array_lower: AccountLoader<'info, TickArray>,
-    #[account(mut, has_one = whirlpool)]
-    pub tick_array_upper: AccountLoader<'info, TickArray>,
+/// Amounts of an executed buy, for the trade event.
+pub struct ExecutedBuy {
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub fee_basis_points: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub referral_fee: u64,
+    //the buy completed the bonding curve
+    pub complete: bool,
 }
 
-pub fn buy(ctx: Context<Buy>, token_amount: u64, max_sol_cost: u64) -> Result<()> {
-    check_buy_sell(
-        &mut ctx.accounts.user_transfer_data,
-        ctx.accounts.user.to_account_info(),
-        ctx.accounts.system_program.to_account_info(),
-        *ctx.accounts.bonding_curve.clone(),
-        token_amount,
-    )?;
+/// Checks a buy of `token_amount`, pays for it and moves the tokens to the user.
+pub fn execute_buy(accounts: BuyAccounts, token_amount: u64, max_sol_cost: u64, presale_proof: Option<PresaleProof>) -> Result<ExecutedBuy> {
+    let BuyAccounts {
+        user,
+        global,
+        launch_config,
+        fee_recipient: fee_recipient_account,
+        mint,
+        bonding_curve,
+        bonding_curve_bump,
+        bonding_curve_token_account,
+        bonding_curve_token_amount,
+        creator_vault,
+        referrer,
+        referrer_stats,
+        user_token_account,
+        user_token_amount,
+        user_transfer_data,
+        user_deposit,
+        quote,
+        system_program,
+        token_program,
+        remaining_accounts,
+    } = accounts;
+    let timestamp = Clock::get()?.unix_timestamp;
+
     require!(
-        ctx.accounts.global.initialized,
+        global.initialized,
         CurveLaunchpadError::NotInitialized
     );
 
+    //program is not paused
+    require!(
+        !global.paused,
+        CurveLaunchpadError::ProgramPaused,
+    );
+
+    //bonding curve is not frozen
+    require!(
+        !bonding_curve.frozen,
+        CurveLaunchpadError::BondingCurveFrozen,
+    );
+
     //bonding curve is not complete
     require!(
-        ctx.accounts.bonding_curve.complete == false,
+        bonding_curve.complete == false,
         CurveLaunchpadError::BondingCurveComplete,
     );
 
+    //bonding curve did not miss its soft cap
+    require!(
+        !bonding_curve.soft_cap_failed(timestamp),
+        CurveLaunchpadError::BondingCurveFailed,
+    );
+
+    require!(
+        bonding_curve.soft_cap.is_none() || user_deposit.is_some(),
+        CurveLaunchpadError::MissingUserDeposit,
+    );
+
+    //deposits are only tracked for refunds, curves without a soft cap never open any
+    require!(
+        bonding_curve.soft_cap.is_some() || user_deposit.is_none(),
+        CurveLaunchpadError::UnexpectedUserDeposit,
+    );
+
+    //paid in the curve's quote mint, SOL without one
+    require!(
+        bonding_curve.is_native_quote() == quote.is_none(),
+        CurveLaunchpadError::InvalidQuoteAccount,
+    );
+
+    let (fee_recipient, base_fee_basis_points) = fee_config(global, bonding_curve, launch_config)?;
+
     //invalid fee recipient
     require!(
-        ctx.accounts.fee_recipient.key == &ctx.accounts.global.fee_recipient,
+        fee_recipient_account.key == &fee_recipient,
         CurveLaunchpadError::InvalidFeeRecipient,
     );
 
+    //the referrer comes with its stats and can't be the user
+    require!(
+        referrer.is_some() == referrer_stats.is_some()
+            && referrer.map_or(true, |referrer| referrer != *user.key),
+        CurveLaunchpadError::InvalidReferrer,
+    );
+
     //bonding curve has enough tokens
     require!(
-        ctx.accounts.bonding_curve.real_token_reserves >= token_amount,
+        bonding_curve.real_token_reserves >= token_amount,
         CurveLaunchpadError::InsufficientTokens,
     );
 
     require!(token_amount > 0, CurveLaunchpadError::MinBuy,);
 
-    let targe_token_amount = if ctx.accounts.bonding_curve_token_account.amount < token_amount {
-        ctx.accounts.bonding_curve_token_account.amount
+    //only allowlisted wallets can buy during the presale, up to their cap
+    let presale_max_token_amount = if bonding_curve.presale_active(timestamp) {
+        let presale = bonding_curve.presale.unwrap();
+        let presale_proof = presale_proof.ok_or(CurveLaunchpadError::NotAllowlisted)?;
+
+        require!(
+            verify_merkle_proof(
+                &presale_proof.proof,
+                &presale.merkle_root,
+                presale_leaf(user.key, presale_proof.max_token_amount),
+            ),
+            CurveLaunchpadError::NotAllowlisted,
+        );
+
+        Some(presale_proof.max_token_amount)
     } else {
-        token_amount
+        None
     };
 
-    let mut amm = amm::amm::AMM::new(
-        ctx.accounts.bonding_curve.virtual_sol_reserves as u128,
-        ctx.accounts.bonding_curve.virtual_token_reserves as u128,
-        ctx.accounts.bonding_curve.real_sol_reserves as u128,
-        ctx.accounts.bonding_curve.real_token_reserves as u128,
-        ctx.accounts.global.initial_virtual_token_reserves as u128,
-    );
+    let targe_token_amount = token_amount.min(bonding_curve_token_amount);
 
-    let buy_result = amm.apply_buy(targe_token_amount as u128).unwrap();
-    let fee = calculate_fee(buy_result.sol_amount, ctx.accounts.global.fee_basis_points);
-    let buy_amount_with_fee = buy_result.sol_amount + fee;
+    let mut curve = bonding_curve.curve();
 
-    //check if the amount of SOL to transfe plus fee is less than the max_sol_cost
-    require!(
-        buy_amount_with_fee <= max_sol_cost,
-        CurveLaunchpadError::MaxSOLCostExceeded,
-    );
+    //the buy reaching the graduation target is clamped to land on it
+    let target = graduation_target(global, bonding_curve, launch_config)?;
+    let graduation_token_amount = bonding_curve.tokens_to_graduate(curve.as_ref(), target, targe_token_amount);
+    let targe_token_amount = graduation_token_amount.unwrap_or(targe_token_amount);
 
-    //check if the user has enough SOL
-    require!(
-        ctx.accounts.user.lamports() >= buy_amount_with_fee,
-        CurveLaunchpadError::InsufficientSOL,
-    );
-    
-    // transfer SOL to bonding curve
-    let from_account = &ctx.accounts.user;
-    let to_bonding_curve_account = &ctx.accounts.bonding_curve;
-
-    let transfer_instruction = system_instruction::transfer(
-        from_account.key,
-        to_bonding_curve_account.to_account_info().key,
-        buy_result.sol_amount,
-    );
+    let buy_result = curve
+        .apply_buy(targe_token_amount as u128)
+        .ok_or(CurveLaunchpadError::InsufficientTokens)?;
 
-    anchor_lang::solana_program::program::invoke_signed(
-        &transfer_instruction,
-        &[
-            from_account.to_account_info(),
-            to_bonding_curve_account.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ],
-        &[],
+    //the rate limit and presale cap are charged for the tokens bought, after clamping
+    check_buy_sell(
+        user_transfer_data,
+        user.clone(),
+        bonding_curve,
+        buy_result.token_amount,
     )?;
 
-    //transfer SOL to fee recipient
-    let to_fee_recipient_account = &ctx.accounts.fee_recipient;
+    if let Some(presale_max_token_amount) = presale_max_token_amount {
+        user_transfer_data.presale_purchased_amount = user_transfer_data
+            .presale_purchased_amount
+            .checked_add(buy_result.token_amount)
+            .filter(|purchased| *purchased <= presale_max_token_amount)
+            .ok_or(CurveLaunchpadError::PresaleLimitExceeded)?;
+    }
 
-    let transfer_instruction = system_instruction::transfer(
-        from_account.key,
-        to_fee_recipient_account.key,
+    let fee_basis_points = bonding_curve.fee_basis_points(base_fee_basis_points, &Clock::get()?);
+    let TradeFees {
         fee,
+        protocol_fee,
+        creator_fee,
+        referral_fee,
+    } = trade_fees(buy_result.sol_amount, fee_basis_points, global, referrer.is_some());
+    let buy_amount_with_fee = buy_result.sol_amount + fee;
+
+    //check if the amount of SOL to transfe plus fee is less than the max_sol_cost
+    require!(
+        buy_amount_with_fee <= max_sol_cost,
+        CurveLaunchpadError::MaxSOLCostExceeded,
     );
 
-    anchor_lang::solana_program::program::invoke_signed(
-        &transfer_instruction,
-        &[
-            from_account.to_account_info(),
-            to_fee_recipient_account.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ],
-        &[],
-    )?;
+    match quote {
+        None => {
+            //check if the user has enough SOL
+            require!(
+                user.lamports() >= buy_amount_with_fee,
+                CurveLaunchpadError::InsufficientSOL,
+            );
+
+            //SOL goes to the bonding curve, the fee recipient, the creator vault and
+            //the referrer stats, claimed by the referrer later
+            let mut payees = vec![
+                (bonding_curve.to_account_info(), buy_result.sol_amount),
+                (fee_recipient_account, protocol_fee),
+                (creator_vault, creator_fee),
+            ];
+            if let Some(referrer_stats) = referrer_stats.as_ref() {
+                payees.push((referrer_stats.to_account_info(), referral_fee));
+            }
+
+            for (to, lamports) in payees {
+                if lamports > 0 {
+                    system_program::transfer(
+                        CpiContext::new(
+                            system_program.clone(),
+                            system_program::Transfer {
+                                from: user.clone(),
+                                to,
+                            },
+                        ),
+                        lamports,
+                    )?;
+                }
+            }
+        }
+        Some(quote) => {
+            //check if the user has enough of the quote mint
+            require!(
+                quote.user_quote_account.amount >= buy_amount_with_fee,
+                CurveLaunchpadError::InsufficientSOL,
+            );
+
+            let mut payees = vec![
+                (quote.quote_vault, buy_result.sol_amount),
+                (quote.fee_recipient_quote_account, protocol_fee),
+                (quote.creator_quote_account, creator_fee),
+            ];
+            if referrer.is_some() {
+                payees.push((
+                    quote.referrer_quote_account.ok_or(CurveLaunchpadError::InvalidQuoteAccount)?,
+                    referral_fee,
+                ));
+            }
+
+            for (to, amount) in payees {
+                transfer_quote(
+                    quote.quote_token_program.clone(),
+                    quote.user_quote_account.to_account_info(),
+                    quote.quote_mint,
+                    to,
+                    user.clone(),
+                    amount,
+                    &[],
+                )?;
+            }
+        }
+    }
 
-    //transfer SPL
-    let cpi_accounts = TransferChecked {
-        from: ctx
-            .accounts
-            .bonding_curve_token_account
-            .to_account_info()
-            .clone(),
-        to: ctx.accounts.user_token_account.to_account_info().clone(),
-        authority: ctx.accounts.bonding_curve.to_account_info().clone(),
-        mint: ctx.accounts.mint.to_account_info().clone(),
-    };
+    //the transfer hook reads the bonding curve, so it has to be written out first
+    bonding_curve.exit(&crate::ID)?;
 
+    //transfer SPL, the user receives it net of the Token-2022 transfer fee
+    let received_token_amount = buy_result.token_amount - transfer_fee(&mint, buy_result.token_amount)?;
     let signer: [&[&[u8]]; 1] = [&[
         BondingCurve::SEED_PREFIX,
-        ctx.accounts.bonding_curve.creator.as_ref(),
-        &[ctx.bumps.bonding_curve],
+        bonding_curve.mint.as_ref(),
+        &[bonding_curve_bump],
     ]];
 
-    token::transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-            &signer,
-        ),
+    transfer_checked_with_hook(
+        token_program,
+        bonding_curve_token_account,
+        mint.clone(),
+        user_token_account,
+        bonding_curve.to_account_info(),
+        remaining_accounts,
         buy_result.token_amount,
-        crate::DEFAULT_DECIMALS.try_into().unwrap()
+        crate::DEFAULT_DECIMALS.try_into().unwrap(),
+        &signer,
     )?;
 
+    //whales can't hold more than the cap while on the curve
+    check_max_wallet(
+        bonding_curve.token_total_supply,
+        bonding_curve.max_wallet_basis_points,
+        user_token_amount.saturating_add(received_token_amount),
+    )?;
+
+    //record the referred trade, the totals are lamports so trades in a quote mint aren't counted
+    if let Some(referrer_stats) = referrer_stats {
+        referrer_stats.referrer = referrer.unwrap();
+        if bonding_curve.is_native_quote() {
+            referrer_stats.total_volume = referrer_stats.total_volume.saturating_add(buy_result.sol_amount);
+            referrer_stats.total_earned = referrer_stats.total_earned.saturating_add(referral_fee);
+        }
+    }
+
+    if let Some(user_deposit) = user_deposit {
+        user_deposit.user = *user.key;
+        user_deposit.mint = *mint.key;
+        user_deposit.record_buy(received_token_amount, buy_result.sol_amount);
+        bonding_curve.deposited_sol_amount = bonding_curve
+            .deposited_sol_amount
+            .checked_add(buy_result.sol_amount)
+            .ok_or(CurveLaunchpadError::MathOverflow)?;
+    }
+
     //apply the buy to the bonding curve
-    let bonding_curve = &mut ctx.accounts.bonding_curve;
-    bonding_curve.real_token_reserves = amm.real_token_reserves as u64;
-    bonding_curve.real_sol_reserves = amm.real_sol_reserves as u64;
-    bonding_curve.virtual_token_reserves = amm.virtual_token_reserves as u64;
-    bonding_curve.virtual_sol_reserves = amm.virtual_sol_reserves as u64;
+    bonding_curve.set_reserves(curve.reserves());
 
-    emit_cpi!(TradeEvent {
-        mint: *ctx.accounts.mint.to_account_info().key,
+    let complete = graduation_token_amount.is_some() || bonding_curve.real_token_reserves == 0;
+    if complete {
+        //the tokens left are kept for the migration liquidity
+        bonding_curve.complete = true;
+    }
+
+    msg!("bonding_curve: {:?}", curve);
+
+    Ok(ExecutedBuy {
         sol_amount: buy_result.sol_amount,
         token_amount: buy_result.token_amount,
+        fee_basis_points,
+        protocol_fee,
+        creator_fee,
+        referral_fee,
+        complete,
+    })
+}
+
+pub fn buy<'info>(ctx: Context<'_, '_, '_, 'info, Buy<'info>>, token_amount: u64, max_sol_cost: u64, presale_proof: Option<PresaleProof>) -> Result<()> {
+    let quote = if ctx.accounts.bonding_curve.is_native_quote() {
+        None
+    } else {
+        let (quote_mint, quote_vault, quote_token_program) = quote_accounts(
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.quote_token_program,
+        )?;
+        let (fee_recipient, _) = fee_config(
+            &ctx.accounts.global,
+            &ctx.accounts.bonding_curve,
+            &ctx.accounts.launch_config,
+        )?;
+
+        Some(BuyQuoteAccounts {
+            quote_mint,
+            quote_vault: quote_vault.to_account_info(),
+            quote_token_program: quote_token_program.to_account_info(),
+            user_quote_account: quote_account(&ctx.accounts.user_quote_account, &quote_mint.key(), ctx.accounts.user.key)?,
+            fee_recipient_quote_account: quote_account(&ctx.accounts.fee_recipient_quote_account, &quote_mint.key(), &fee_recipient)?.to_account_info(),
+            creator_quote_account: quote_account(&ctx.accounts.creator_quote_account, &quote_mint.key(), &ctx.accounts.bonding_curve.creator)?.to_account_info(),
+            referrer_quote_account: match ctx.accounts.referrer.as_ref() {
+                Some(referrer) => Some(quote_account(&ctx.accounts.referrer_quote_account, &quote_mint.key(), referrer.key)?.to_account_info()),
+                None => None,
+            },
+        })
+    };
+
+    let executed_buy = execute_buy(
+        BuyAccounts {
+            user: ctx.accounts.user.to_account_info(),
+            global: &ctx.accounts.global,
+            launch_config: &ctx.accounts.launch_config,
+            fee_recipient: ctx.accounts.fee_recipient.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            bonding_curve: &mut ctx.accounts.bonding_curve,
+            bonding_curve_bump: ctx.bumps.bonding_curve,
+            bonding_curve_token_account: ctx.accounts.bonding_curve_token_account.to_account_info(),
+            bonding_curve_token_amount: ctx.accounts.bonding_curve_token_account.amount,
+            creator_vault: ctx.accounts.creator_vault.to_account_info(),
+            referrer: ctx.accounts.referrer.as_ref().map(|referrer| referrer.key()),
+            referrer_stats: ctx.accounts.referrer_stats.as_deref_mut(),
+            user_token_account: ctx.accounts.user_token_account.to_account_info(),
+            user_token_amount: ctx.accounts.user_token_account.amount,
+            user_transfer_data: &mut ctx.accounts.user_transfer_data,
+            user_deposit: ctx.accounts.user_deposit.as_deref_mut(),
+            quote,
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            remaining_accounts: ctx.remaining_accounts,
+        },
+        token_amount,
+        max_sol_cost,
+        presale_proof,
+    )?;
+
+    ctx.accounts.creator_vault.bonding_curve = ctx.accounts.bonding_curve.key();
+
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    emit_cpi!(TradeEvent {
+        mint: *ctx.accounts.mint.to_account_info().key,
+        sol_amount: executed_buy.sol_amount,
+        token_amount: executed_buy.token_amount,
         is_buy: true,
         user: *ctx.accounts.user.to_account_info().key,
         timestamp: Clock::get()?.unix_timestamp,
@@ -319,20 +539,56 @@ pub fn buy(ctx: Context<Buy>, token_amount: u64, max_sol_cost: u64) -> Result<()
         virtual_token_reserves: bonding_curve.virtual_token_reserves,
         real_sol_reserves: bonding_curve.real_sol_reserves,
         real_token_reserves: bonding_curve.real_token_reserves,
+        fee: executed_buy.protocol_fee,
+        fee_basis_points: executed_buy.fee_basis_points,
+        creator_fee: executed_buy.creator_fee,
+        referrer: ctx.accounts.referrer.as_ref().map(|referrer| referrer.key()),
+        referral_fee: executed_buy.referral_fee,
     });
 
-    if bonding_curve.real_token_reserves == 0 {
-        bonding_curve.complete = true;
-
+    if executed_buy.complete {
         emit_cpi!(CompleteEvent {
             user: *ctx.accounts.user.to_account_info().key,
             mint: *ctx.accounts.mint.to_account_info().key,
             bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
             timestamp: Clock::get()?.unix_timestamp,
+            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            real_sol_reserves: bonding_curve.real_sol_reserves,
+            real_token_reserves: bonding_curve.real_token_reserves,
         });
     }
 
-    msg!("bonding_curve: {:?}", amm);
-
     Ok(())
 }
+
+pub fn buy_exact_in<'info>(ctx: Context<'_, '_, '_, 'info, Buy<'info>>, sol_amount: u64, min_token_output: u64, presale_proof: Option<PresaleProof>) -> Result<()> {
+    //bonding curve is not complete
+    require!(
+        ctx.accounts.bonding_curve.complete == false,
+        CurveLaunchpadError::BondingCurveComplete,
+    );
+
+    let (_, base_fee_basis_points) = fee_config(
+        &ctx.accounts.global,
+        &ctx.accounts.bonding_curve,
+        &ctx.accounts.launch_config,
+    )?;
+    let curve = ctx.accounts.bonding_curve.curve();
+
+    let token_amount = curve
+        .get_tokens_for_sol(sol_amount as u128, ctx
+            .accounts
+            .bonding_curve
+            .fee_basis_points(base_fee_basis_points, &Clock::get()?) as u128)
+        .ok_or(CurveLaunchpadError::MinBuy)? as u64;
+
+    //confirm the user gets at least min_token_output, net of the transfer fee
+    require!(
+        token_amount - transfer_fee(&ctx.accounts.mint.to_account_info(), token_amount)? >= min_token_output,
+        CurveLaunchpadError::MinTokenOutputExceeded,
+    );
+
+    //sol_amount is the max cost, so the buy never spends more than requested
+    buy(ctx, token_amount, sol_amount, presale_proof)
+}
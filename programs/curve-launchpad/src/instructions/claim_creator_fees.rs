@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    state::{BondingCurve, CreatorVault},
+    transfer_checked_with_hook, ClaimCreatorFeesEvent, CurveLaunchpadError,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    creator: Signer<'info>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+        has_one = creator @ CurveLaunchpadError::InvalidCreator,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        seeds = [CreatorVault::SEED_PREFIX, bonding_curve.key().as_ref()],
+        bump,
+    )]
+    creator_vault: Box<Account<'info, CreatorVault>>,
+
+    //only to also claim the withheld transfer fees paid to the vault
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = creator_vault,
+        associated_token::token_program = token_program,
+    )]
+    creator_vault_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = creator,
+    )]
+    creator_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn claim_creator_fees<'info>(ctx: Context<'_, '_, '_, 'info, ClaimCreatorFees<'info>>) -> Result<()> {
+    //everything above the rent exempt balance is accrued fees
+    let creator_vault = ctx.accounts.creator_vault.to_account_info();
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(creator_vault.data_len());
+    let sol_amount = creator_vault.lamports().saturating_sub(rent_exempt_lamports);
+
+    let token_amount = ctx
+        .accounts
+        .creator_vault_token_account
+        .as_ref()
+        .map_or(0, |creator_vault_token_account| creator_vault_token_account.amount);
+
+    require!(sol_amount > 0 || token_amount > 0, CurveLaunchpadError::NoCreatorFeesToClaim);
+
+    **creator_vault.try_borrow_mut_lamports()? -= sol_amount;
+    **ctx.accounts.creator.try_borrow_mut_lamports()? += sol_amount;
+
+    if let (Some(creator_vault_token_account), Some(creator_token_account), Some(token_program)) = (
+        ctx.accounts.creator_vault_token_account.as_ref(),
+        ctx.accounts.creator_token_account.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+    ) {
+        if token_amount > 0 {
+            let bonding_curve = ctx.accounts.bonding_curve.key();
+            let signer: [&[&[u8]]; 1] = [&[
+                CreatorVault::SEED_PREFIX,
+                bonding_curve.as_ref(),
+                &[ctx.bumps.creator_vault],
+            ]];
+
+            transfer_checked_with_hook(
+                token_program.to_account_info(),
+                creator_vault_token_account.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                creator_token_account.to_account_info(),
+                ctx.accounts.creator_vault.to_account_info(),
+                ctx.remaining_accounts,
+                token_amount,
+                ctx.accounts.mint.decimals,
+                &signer,
+            )?;
+        }
+    } else {
+        require!(token_amount == 0, ErrorCode::AccountNotEnoughKeys);
+    }
+
+    emit_cpi!(ClaimCreatorFeesEvent {
+        creator: *ctx.accounts.creator.to_account_info().key,
+        mint: *ctx.accounts.mint.to_account_info().key,
+        bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
+        sol_amount,
+        token_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{state::ReferrerStats, ClaimReferralFeesEvent, CurveLaunchpadError};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(mut)]
+    referrer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ReferrerStats::SEED_PREFIX, referrer.key().as_ref()],
+        bump,
+        has_one = referrer @ CurveLaunchpadError::InvalidReferrer,
+    )]
+    referrer_stats: Box<Account<'info, ReferrerStats>>,
+}
+
+pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+    //everything above the rent exempt balance is accrued fees
+    let referrer_stats = ctx.accounts.referrer_stats.to_account_info();
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(referrer_stats.data_len());
+    let sol_amount = referrer_stats.lamports().saturating_sub(rent_exempt_lamports);
+
+    require!(sol_amount > 0, CurveLaunchpadError::NoReferralFeesToClaim);
+
+    **referrer_stats.try_borrow_mut_lamports()? -= sol_amount;
+    **ctx.accounts.referrer.try_borrow_mut_lamports()? += sol_amount;
+
+    emit_cpi!(ClaimReferralFeesEvent {
+        referrer: *ctx.accounts.referrer.to_account_info().key,
+        sol_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
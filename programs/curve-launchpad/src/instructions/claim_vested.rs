@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    state::Vesting, transfer_checked_with_hook, vested_amount, ClaimVestedEvent, CurveLaunchpadError,
+    DEFAULT_DECIMALS,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(mut)]
+    creator: Signer<'info>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [Vesting::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+        has_one = creator @ CurveLaunchpadError::InvalidCreator,
+    )]
+    vesting: Box<Account<'info, Vesting>>,
+
+    #[account(
+        mut,
+        address = vesting.token_account,
+    )]
+    vesting_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = creator,
+    )]
+    creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn claim_vested<'info>(ctx: Context<'_, '_, '_, 'info, ClaimVested<'info>>) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let vesting = &ctx.accounts.vesting;
+
+    let vested = vested_amount(
+        vesting.total_amount,
+        timestamp.saturating_sub(vesting.start_timestamp),
+        vesting.cliff_seconds,
+        vesting.duration_seconds,
+    );
+    let token_amount = vested.saturating_sub(vesting.claimed_amount);
+
+    require!(token_amount > 0, CurveLaunchpadError::NoVestedTokensToClaim);
+
+    let mint_key = ctx.accounts.mint.key();
+    let seeds = &[
+        Vesting::SEED_PREFIX.as_ref(),
+        mint_key.as_ref(),
+        &[ctx.bumps.vesting],
+    ];
+    let signer = [&seeds[..]];
+
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.vesting_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.creator_token_account.to_account_info(),
+        ctx.accounts.vesting.to_account_info(),
+        ctx.remaining_accounts,
+        token_amount,
+        DEFAULT_DECIMALS as u8,
+        &signer,
+    )?;
+
+    ctx.accounts.vesting.claimed_amount += token_amount;
+
+    emit_cpi!(ClaimVestedEvent {
+        creator: *ctx.accounts.creator.to_account_info().key,
+        mint: *ctx.accounts.mint.to_account_info().key,
+        vesting: *ctx.accounts.vesting.to_account_info().key,
+        token_amount,
+        timestamp,
+    });
+
+    Ok(())
+}
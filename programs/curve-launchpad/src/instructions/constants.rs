@@ -1,3 +1,23 @@
 pub const DEFAULT_DECIMALS: u32 = 6;
 pub const DEFAULT_TOKEN_LAMPORTS: u64 = (10 as u64).pow(DEFAULT_DECIMALS);
-pub const DEFAULT_TOKEN_SUPPLY: u64 = 1_000_000_000 * DEFAULT_TOKEN_LAMPORTS;
\ No newline at end of file
+pub const DEFAULT_TOKEN_SUPPLY: u64 = 1_000_000_000 * DEFAULT_TOKEN_LAMPORTS;
+pub const WITHDRAW_COOLDOWN_SECONDS: i64 = 60 * 60 * 24;
+pub const WHIRLPOOL_TICK_SPACING: u16 = 256;
+//default rate limit policy, the creator may move 0.5% of the supply per hour
+pub const DEFAULT_CREATOR_TRANSFER_LIMIT_BASIS_POINTS: u64 = 50;
+pub const DEFAULT_USER_TRANSFER_LIMIT_BASIS_POINTS: u64 = 0;
+pub const DEFAULT_TRANSFER_WINDOW_SECONDS: i64 = 60 * 60;
+
+//exponential launch fees are down to ~0.1% of the elevated part when the schedule ends
+pub const LAUNCH_FEE_HALVINGS: u64 = 10;
+
+//token metadata limits
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+pub const MAX_METADATA_KEY_LENGTH: usize = 32;
+pub const MAX_METADATA_VALUE_LENGTH: usize = 200;
+pub const MAX_ADDITIONAL_METADATA_FIELDS: usize = 8;
+
+//an existing whirlpool is only migrated into if it opens within ~1% of the curve price
+pub const WHIRLPOOL_MAX_SQRT_PRICE_DEVIATION_BPS: u64 = 50;
\ No newline at end of file
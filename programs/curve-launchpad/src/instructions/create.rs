@@ -1,11 +1,11 @@
 use crate::{
-    state::{BondingCurve, Global, Team}, CreateEvent, CurveLaunchpadError, DEFAULT_DECIMALS
+    check_metadata_fields, execute_buy, fee_config, is_supported_quote_mint, quote_account, update_token_metadata, state::{BondingCurve, CreatorVault, CurveKind, Global, LaunchConfig, LaunchFeeSchedule, LaunchParams, Presale, RateLimitPolicy, SoftCap, Team, TransferFeeParams, UserDeposit, UserTransferData, Vesting, VestingParams}, BuyAccounts, BuyQuoteAccounts, CompleteEvent, CreateEvent, CurveLaunchpadError, TradeEvent, DEFAULT_DECIMALS
 };
 
 use anchor_lang::{prelude::*, solana_program::program::{invoke, invoke_signed}, system_program::{create_account, CreateAccount}};
 use anchor_spl::{
-    associated_token::AssociatedToken, token_2022::{self, SetAuthority}, token_2022_extensions, token_interface::{
-        self as token, metadata_pointer_initialize, mint_to, spl_token_2022::instruction::AuthorityType, spl_token_metadata_interface::instruction::initialize, Mint, MintTo, TokenAccount, TokenInterface
+    associated_token::{self, AssociatedToken}, token_2022, token_2022_extensions, token_interface::{
+        self as token, metadata_pointer_initialize, mint_to, spl_token_2022::instruction::AuthorityType, spl_token_metadata_interface::{instruction::initialize, state::Field}, Mint, MintTo, TokenAccount, TokenInterface
     }
 };
 use spl_associated_token_account::instruction::AssociatedTokenAccountInstruction;
@@ -35,7 +35,7 @@ pub struct Create<'info> {
     #[account(
         init,
         payer = creator,
-        seeds = [BondingCurve::SEED_PREFIX, creator.to_account_info().key.as_ref()],
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
         bump,
         space = 8 + BondingCurve::INIT_SPACE,
     )]
@@ -68,11 +68,118 @@ pub struct Create<'info> {
         bump
     )]
     pub extra_account_meta_list: AccountInfo<'info>,
-    
+
+    //the creator's transfers are rate limited by the transfer hook
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + UserTransferData::INIT_SPACE,
+        seeds = [b"user", creator.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    creator_transfer_data: Box<Account<'info, UserTransferData>>,
+
+    //only needed when the creator keeps a vested allocation
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Vesting::INIT_SPACE,
+        seeds = [Vesting::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    vesting: Option<Box<Account<'info, Vesting>>>,
+
+    #[account(
+        mut
+    )]
+    /// CHECK: associated token account of the vesting PDA, created here
+    vesting_token_account: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + CreatorVault::INIT_SPACE,
+        seeds = [CreatorVault::SEED_PREFIX, bonding_curve.key().as_ref()],
+        bump,
+    )]
+    creator_vault: Box<Account<'info, CreatorVault>>,
+
+    //only needed for the initial buy
+    /// CHECK: Using global state to validate fee_recipient account
+    #[account(mut)]
+    fee_recipient: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut
+    )]
+    /// CHECK: associated token account of the creator, created here for the initial buy
+    creator_token_account: Option<UncheckedAccount<'info>>,
+
+    //required for an initial buy on soft-cap curves, so it can be refunded
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + UserDeposit::INIT_SPACE,
+        seeds = [UserDeposit::SEED_PREFIX, mint.key().as_ref(), creator.key().as_ref()],
+        bump,
+    )]
+    creator_deposit: Option<Box<Account<'info, UserDeposit>>>,
+
+    //only for curves priced in an SPL quote mint instead of SOL
+    quote_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut
+    )]
+    /// CHECK: associated token account of the bonding curve in the quote mint, created here
+    quote_vault: Option<UncheckedAccount<'info>>,
+
+    quote_token_program: Option<Interface<'info, TokenInterface>>,
+
+    //pay the initial buy on curves priced in an SPL quote mint
+    #[account(mut)]
+    creator_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    fee_recipient_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    //only for curves created under a launch config instead of `Global`
+    launch_config: Option<Box<Account<'info, LaunchConfig>>>,
+}
+
+/// Buy made by the creator in the same instruction as the launch, so it can't be front-run.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitialBuy {
+    pub token_amount: u64,
+    pub max_sol_cost: u64,
 }
 
+/// Additional token metadata, e.g. the website, twitter, telegram or description of the token.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MetadataField {
+    pub key: String,
+    pub value: String,
+}
+
+
+pub fn create<'info>(ctx: Context<'_, '_, '_, 'info, Create<'info>>, name: String, symbol: String, uri: String, team: Team, curve_kind: CurveKind, rate_limit_policy: RateLimitPolicy, vesting_params: Option<VestingParams>, initial_buy: Option<InitialBuy>, launch_fee_schedule: Option<LaunchFeeSchedule>, max_wallet_basis_points: u64, presale: Option<Presale>, soft_cap: Option<SoftCap>, launch_params: Option<LaunchParams>, transfer_fee: TransferFeeParams, additional_metadata: Vec<MetadataField>, transfer_hook: bool) -> Result<()> {
+    //curves launch under their launch config, or under `Global` without one
+    let (default_launch_params, default_launch_fee_schedule) = match &ctx.accounts.launch_config {
+        Some(launch_config) => (launch_config.launch_params, launch_config.launch_fee_schedule),
+        None => (ctx.accounts.global.launch_params(), ctx.accounts.global.launch_fee_schedule),
+    };
+    ctx.accounts.bonding_curve.launch_config = ctx
+        .accounts
+        .launch_config
+        .as_ref()
+        .map(|launch_config| launch_config.key())
+        .unwrap_or_default();
+    let (fee_recipient, _) = fee_config(
+        &ctx.accounts.global,
+        &ctx.accounts.bonding_curve,
+        &ctx.accounts.launch_config,
+    )?;
 
-pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, team: Team) -> Result<()> {
     //confirm program is initialized
     {
         require!(
@@ -80,6 +187,119 @@ pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, t
             CurveLaunchpadError::NotInitialized
         );
 
+        //program is not paused
+        require!(
+            !ctx.accounts.global.paused,
+            CurveLaunchpadError::ProgramPaused,
+        );
+
+        require!(
+            rate_limit_policy.is_valid(),
+            CurveLaunchpadError::InvalidRateLimitPolicy,
+        );
+
+        //overridden launch fees have to stay below the cap set by the authority
+        if let Some(launch_fee_schedule) = &launch_fee_schedule {
+            require!(
+                launch_fee_schedule.is_valid()
+                    && launch_fee_schedule.is_within(ctx.accounts.global.max_launch_fee_basis_points),
+                CurveLaunchpadError::InvalidLaunchFeeSchedule,
+            );
+        }
+
+        require!(
+            max_wallet_basis_points <= 10000,
+            CurveLaunchpadError::InvalidMaxWallet,
+        );
+
+        //overridden launch params have to stay within the limits set by the authority
+        if let Some(launch_params) = &launch_params {
+            require!(
+                launch_params.is_valid()
+                    && ctx.accounts.global.launch_params_limits.contains(launch_params),
+                CurveLaunchpadError::InvalidLaunchParams,
+            );
+        }
+
+        check_metadata_fields(&[
+            (Field::Name, name.clone()),
+            (Field::Symbol, symbol.clone()),
+            (Field::Uri, uri.clone()),
+        ])?;
+
+        //the transfer fee can't be changed after launch, so it has to stay within the limits set by the authority
+        require!(
+            transfer_fee.is_within(&ctx.accounts.global.max_transfer_fee),
+            CurveLaunchpadError::InvalidTransferFee,
+        );
+
+        //presale has to end in the future, and the creator can't buy ahead of the allowlist
+        if let Some(presale) = &presale {
+            require!(
+                presale.end_timestamp > Clock::get()?.unix_timestamp
+                    && initial_buy.is_none(),
+                CurveLaunchpadError::InvalidPresale,
+            );
+        }
+
+        //soft cap has to be reachable and end in the future
+        if let Some(soft_cap) = &soft_cap {
+            require!(
+                soft_cap.min_real_sol_reserves > 0
+                    && soft_cap.deadline > Clock::get()?.unix_timestamp,
+                CurveLaunchpadError::InvalidSoftCap,
+            );
+        }
+
+        //vesting allocation comes out of the tokens sold on the curve
+        if let Some(vesting_params) = &vesting_params {
+            require!(
+                vesting_params.is_valid()
+                    && vesting_params.amount < launch_params.unwrap_or(default_launch_params).initial_real_token_reserves
+                    && ctx.accounts.vesting.is_some()
+                    && ctx.accounts.vesting_token_account.is_some(),
+                CurveLaunchpadError::InvalidVestingParams,
+            );
+        }
+
+        if let Some(quote_mint) = &ctx.accounts.quote_mint {
+            require!(
+                ctx.accounts.quote_vault.is_some() && ctx.accounts.quote_token_program.is_some(),
+                CurveLaunchpadError::InvalidQuoteAccount,
+            );
+
+            require!(
+                is_supported_quote_mint(&quote_mint.to_account_info())?,
+                CurveLaunchpadError::QuoteMintNotSupported,
+            );
+
+            //the `Global` params are lamports, quote curves launch under a launch config priced in their quote mint
+            require!(
+                ctx.accounts.launch_config.as_ref().is_some_and(|launch_config| launch_config.quote_mint == quote_mint.key())
+                    && launch_params.is_none(),
+                CurveLaunchpadError::InvalidLaunchConfig,
+            );
+        } else if let Some(launch_config) = &ctx.accounts.launch_config {
+            //curves priced in SOL can't launch under a launch config priced in a quote mint
+            require!(
+                launch_config.quote_mint == Pubkey::default(),
+                CurveLaunchpadError::InvalidLaunchConfig,
+            );
+        }
+
+        if initial_buy.is_some() {
+            //invalid fee recipient
+            require!(
+                ctx.accounts.fee_recipient.is_some(),
+                CurveLaunchpadError::InvalidFeeRecipient,
+            );
+
+            require!(
+                ctx.accounts.creator_token_account.is_some(),
+                ErrorCode::AccountNotEnoughKeys,
+            );
+        }
+
         msg!("create::BondingCurve::get_lamports: {:?}", &ctx.accounts.bonding_curve.get_lamports());
     }
     let seeds = &["mint-authority".as_bytes(), &[ctx.bumps.mint_authority]];
@@ -104,13 +324,30 @@ pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, t
         set_transfer_fee_accounts,
         &signer,
     );
+    //withheld fees can only be withdrawn by the program, into the creator vault
     token_2022_extensions::transfer_fee_initialize(
         set_transfer_fee_ctx,
-        Some(&ctx.accounts.mint_authority.to_account_info().key),
-        Some(&ctx.accounts.creator.to_account_info().key),
-        10,
-        0, // Maximum fee (1% of total supply)
+        None,
+        Some(ctx.accounts.mint_authority.to_account_info().key),
+        transfer_fee.transfer_fee_basis_points,
+        transfer_fee.maximum_fee,
     )?;
+
+    // Route every transfer through this program's transfer hook. Hooked mints
+    // need a token badge from the whirlpools config to migrate
+    if transfer_hook {
+        token_2022_extensions::transfer_hook_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022_extensions::TransferHookInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            None,
+            Some(crate::ID),
+        )?;
+    }
     
     let cpi_accounts = token::InitializeMint {
         mint: ctx.accounts.mint.to_account_info(),
@@ -143,22 +380,21 @@ pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, t
         ctx.accounts.mint_authority.to_account_info(),
     ];
     invoke_signed(&ix, &accounts, &signer)?;
-    // Set transfer fee
-    // Set fee authority to creator
-    let set_authority_accounts = SetAuthority {
-        account_or_mint: ctx.accounts.mint.to_account_info(),
-        current_authority: ctx.accounts.mint_authority.to_account_info(),
-    };
-    let set_authority_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        set_authority_accounts,
-        &signer,
-    );
-    token_2022::set_authority(
-        set_authority_ctx,
-        AuthorityType::TransferFeeConfig,
-        Some(ctx.accounts.creator.key()),
-    )?;
+
+    if !additional_metadata.is_empty() {
+        update_token_metadata(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.mint_authority.to_account_info(),
+            ctx.accounts.creator.to_account_info(),
+            additional_metadata
+                .into_iter()
+                .map(|metadata_field| (Field::Key(metadata_field.key), metadata_field.value))
+                .collect(),
+            &signer,
+        )?;
+    }
     // Create the ExtraAccountMetaList account
   
   let data = 0u8;
@@ -186,31 +422,31 @@ pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, t
     invoke(&ix, &accounts)?;
 
 }
-{
+//without the hook only trades against the curve are rate limited, not wallet to wallet transfers
+if transfer_hook {
      // index 0-3 are the accounts required for token transfer (source, mint, destination, owner)
         // index 4 is address of ExtraAccountMetaList account
         // The `addExtraAccountsToInstruction` JS helper function resolving incorrectly
         let account_metas = vec![
-            // index 5, wrapped SOL mint    
-            // index 6, token program
-            ExtraAccountMeta::new_with_seeds(
-                &[Seed::Literal {
-                    bytes: "delegate".as_bytes().to_vec(),
-                }],
-                false,
-                true
-            )?,
+            // index 5, transfer data of the source token account owner
             ExtraAccountMeta::new_with_seeds(
                 &[Seed::Literal {
                     bytes: "user".as_bytes().to_vec(),
-                }, Seed::AccountKey { index: 3 },
+                }, Seed::AccountData { account_index: 0, data_index: 32, length: 32 },
                 Seed::AccountKey { index: 1 },
                 ],
                 false, // is_signer
                 true,  // is_writable
             )?,
-            ExtraAccountMeta::new_with_pubkey(&ctx.accounts.system_program.key(), false, false)?,
-            ExtraAccountMeta::new_with_pubkey(&ctx.accounts.bonding_curve.key(), false, true)?,
+            // index 6, bonding curve
+            ExtraAccountMeta::new_with_seeds(
+                &[Seed::Literal {
+                    bytes: BondingCurve::SEED_PREFIX.to_vec(),
+                }, Seed::AccountKey { index: 1 },
+                ],
+                false, // is_signer
+                false, // is_writable
+            )?,
         ];
 
         // calculate account size
@@ -246,6 +482,8 @@ pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, t
             &account_metas,
         )?;
 }
+let launch_params = launch_params.unwrap_or(default_launch_params);
+let vested_amount = vesting_params.map(|vesting_params| vesting_params.amount).unwrap_or(0);
 {
     //mint tokens to bonding_curve_token_account
     mint_to(
@@ -258,9 +496,50 @@ pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, t
             },
             &signer,
         ),
-        ctx.accounts.global.initial_token_supply,
+        launch_params.initial_token_supply - vested_amount,
     )?;
 
+    //mint the creator allocation to the vesting PDA
+    if let (Some(vesting_params), Some(vesting), Some(vesting_token_account)) = (
+        vesting_params,
+        ctx.accounts.vesting.as_mut(),
+        ctx.accounts.vesting_token_account.as_ref(),
+    ) {
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.creator.to_account_info(),
+                associated_token: vesting_token_account.to_account_info(),
+                authority: vesting.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                    to: vesting_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+                &signer,
+            ),
+            vested_amount,
+        )?;
+
+        vesting.creator = *ctx.accounts.creator.to_account_info().key;
+        vesting.mint = *ctx.accounts.mint.to_account_info().key;
+        vesting.token_account = *vesting_token_account.to_account_info().key;
+        vesting.total_amount = vesting_params.amount;
+        vesting.claimed_amount = 0;
+        vesting.start_timestamp = Clock::get()?.unix_timestamp;
+        vesting.cliff_seconds = vesting_params.cliff_seconds;
+        vesting.duration_seconds = vesting_params.duration_seconds;
+    }
+
     //remove mint_authority
     let cpi_context = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
@@ -274,14 +553,46 @@ pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, t
 }
 {
     let bonding_curve = &mut ctx.accounts.bonding_curve;
-    bonding_curve.virtual_sol_reserves = ctx.accounts.global.initial_virtual_sol_reserves;
-    bonding_curve.virtual_token_reserves = ctx.accounts.global.initial_virtual_token_reserves;
+    bonding_curve.virtual_sol_reserves = launch_params.initial_virtual_sol_reserves;
+    bonding_curve.virtual_token_reserves = launch_params.initial_virtual_token_reserves;
     bonding_curve.real_sol_reserves = 0;
-    bonding_curve.real_token_reserves = ctx.accounts.global.initial_real_token_reserves;
-    bonding_curve.token_total_supply = ctx.accounts.global.initial_token_supply;
+    bonding_curve.real_token_reserves = launch_params.initial_real_token_reserves - vested_amount;
+    bonding_curve.token_total_supply = launch_params.initial_token_supply;
+    bonding_curve.launch_params = launch_params;
     bonding_curve.complete = false;
     bonding_curve.creator = *ctx.accounts.creator.to_account_info().key;
+    bonding_curve.mint = *ctx.accounts.mint.to_account_info().key;
     bonding_curve.team = team;
+    bonding_curve.curve_kind = curve_kind;
+    bonding_curve.rate_limit_policy = rate_limit_policy;
+    bonding_curve.launch_slot = Clock::get()?.slot;
+    bonding_curve.launch_timestamp = Clock::get()?.unix_timestamp;
+    bonding_curve.launch_fee_schedule = launch_fee_schedule.unwrap_or(default_launch_fee_schedule);
+    bonding_curve.max_wallet_basis_points = max_wallet_basis_points;
+    bonding_curve.presale = presale;
+    bonding_curve.soft_cap = soft_cap;
+
+    //the quote reserves of an SPL quote mint are held by the bonding curve
+    if let (Some(quote_mint), Some(quote_vault), Some(quote_token_program)) = (
+        ctx.accounts.quote_mint.as_ref(),
+        ctx.accounts.quote_vault.as_ref(),
+        ctx.accounts.quote_token_program.as_ref(),
+    ) {
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.creator.to_account_info(),
+                associated_token: quote_vault.to_account_info(),
+                authority: bonding_curve.to_account_info(),
+                mint: quote_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: quote_token_program.to_account_info(),
+            },
+        ))?;
+
+        bonding_curve.quote_mint = quote_mint.key();
+        bonding_curve.quote_vault = quote_vault.key();
+    }
     bonding_curve.token_account = *ctx.accounts.bonding_curve_token_account.to_account_info().key;
 
     emit_cpi!(CreateEvent {
@@ -291,8 +602,105 @@ pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, t
         mint: *ctx.accounts.mint.to_account_info().key,
         bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
         creator: *ctx.accounts.creator.to_account_info().key,
+        quote_mint: ctx.accounts.bonding_curve.quote_mint,
     });
 
+    ctx.accounts.creator_vault.bonding_curve = ctx.accounts.bonding_curve.key();
+}
+if let Some(initial_buy) = initial_buy {
+    let creator_token_account = ctx.accounts.creator_token_account.as_ref().unwrap();
+
+    associated_token::create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.creator.to_account_info(),
+            associated_token: creator_token_account.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+    ))?;
+
+    //the creator pays the fees in the quote mint like any buyer, its own account takes the creator fee
+    let quote = match (ctx.accounts.quote_mint.as_deref(), ctx.accounts.quote_vault.as_ref(), ctx.accounts.quote_token_program.as_ref()) {
+        (Some(quote_mint), Some(quote_vault), Some(quote_token_program)) => {
+            let creator_quote_account = quote_account(&ctx.accounts.creator_quote_account, &quote_mint.key(), ctx.accounts.creator.key)?;
+
+            Some(BuyQuoteAccounts {
+                quote_mint,
+                quote_vault: quote_vault.to_account_info(),
+                quote_token_program: quote_token_program.to_account_info(),
+                user_quote_account: creator_quote_account,
+                fee_recipient_quote_account: quote_account(&ctx.accounts.fee_recipient_quote_account, &quote_mint.key(), &fee_recipient)?.to_account_info(),
+                creator_quote_account: creator_quote_account.to_account_info(),
+                referrer_quote_account: None,
+            })
+        }
+        _ => None,
+    };
+
+    //the initial buy goes through the same checks and fees as `buy`
+    let executed_buy = execute_buy(
+        BuyAccounts {
+            user: ctx.accounts.creator.to_account_info(),
+            global: &ctx.accounts.global,
+            launch_config: &ctx.accounts.launch_config,
+            fee_recipient: ctx.accounts.fee_recipient.as_ref().unwrap().to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            bonding_curve: &mut ctx.accounts.bonding_curve,
+            bonding_curve_bump: ctx.bumps.bonding_curve,
+            bonding_curve_token_account: ctx.accounts.bonding_curve_token_account.to_account_info(),
+            bonding_curve_token_amount: launch_params.initial_token_supply - vested_amount,
+            creator_vault: ctx.accounts.creator_vault.to_account_info(),
+            referrer: None,
+            referrer_stats: None,
+            user_token_account: creator_token_account.to_account_info(),
+            //the creator token account was created above
+            user_token_amount: 0,
+            user_transfer_data: &mut ctx.accounts.creator_transfer_data,
+            user_deposit: ctx.accounts.creator_deposit.as_deref_mut(),
+            quote,
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            remaining_accounts: ctx.remaining_accounts,
+        },
+        initial_buy.token_amount,
+        initial_buy.max_sol_cost,
+        None,
+    )?;
+
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    emit_cpi!(TradeEvent {
+        mint: *ctx.accounts.mint.to_account_info().key,
+        sol_amount: executed_buy.sol_amount,
+        token_amount: executed_buy.token_amount,
+        is_buy: true,
+        user: *ctx.accounts.creator.to_account_info().key,
+        timestamp: Clock::get()?.unix_timestamp,
+        virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+        virtual_token_reserves: bonding_curve.virtual_token_reserves,
+        real_sol_reserves: bonding_curve.real_sol_reserves,
+        real_token_reserves: bonding_curve.real_token_reserves,
+        fee: executed_buy.protocol_fee,
+        fee_basis_points: executed_buy.fee_basis_points,
+        creator_fee: executed_buy.creator_fee,
+        referrer: None,
+        referral_fee: executed_buy.referral_fee,
+    });
+
+    if executed_buy.complete {
+        emit_cpi!(CompleteEvent {
+            user: *ctx.accounts.creator.to_account_info().key,
+            mint: *ctx.accounts.mint.to_account_info().key,
+            bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
+            timestamp: Clock::get()?.unix_timestamp,
+            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            real_sol_reserves: bonding_curve.real_sol_reserves,
+            real_token_reserves: bonding_curve.real_token_reserves,
+        });
+    }
 }
     Ok(())
 }
@@ -33,4 +33,98 @@ pub enum CurveLaunchpadError {
     TransferCooldownNotMet,
     #[msg("Transfer Limit Exceeded")]
     TransferLimitExceeded,
+    #[msg("Withdraw Cooldown Not Met")]
+    WithdrawCooldownNotMet,
+    #[msg("Insufficient Liquidity")]
+    InsufficientLiquidity,
+    #[msg("Invalid Pool Price")]
+    InvalidPoolPrice,
+    #[msg("Min Token Output Exceeded")]
+    MinTokenOutputExceeded,
+    #[msg("Max Token Input Exceeded")]
+    MaxTokenInputExceeded,
+    #[msg("Invalid Legacy Bonding Curve")]
+    InvalidLegacyBondingCurve,
+    #[msg("Invalid Creator Fee Share")]
+    InvalidCreatorFeeShare,
+    #[msg("Invalid Creator")]
+    InvalidCreator,
+    #[msg("No Creator Fees To Claim")]
+    NoCreatorFeesToClaim,
+    #[msg("Invalid Referral Fee Share")]
+    InvalidReferralFeeShare,
+    #[msg("Invalid Referrer")]
+    InvalidReferrer,
+    #[msg("Invalid Pending Authority")]
+    InvalidPendingAuthority,
+    #[msg("Invalid Guardian")]
+    InvalidGuardian,
+    #[msg("Program Paused")]
+    ProgramPaused,
+    #[msg("Bonding Curve Frozen")]
+    BondingCurveFrozen,
+    #[msg("Invalid Rate Limit Policy")]
+    InvalidRateLimitPolicy,
+    #[msg("Invalid Vesting Params")]
+    InvalidVestingParams,
+    #[msg("No Vested Tokens To Claim")]
+    NoVestedTokensToClaim,
+    #[msg("Invalid Launch Fee Schedule")]
+    InvalidLaunchFeeSchedule,
+    #[msg("Invalid Max Wallet")]
+    InvalidMaxWallet,
+    #[msg("Max Wallet Exceeded")]
+    MaxWalletExceeded,
+    #[msg("Invalid Presale")]
+    InvalidPresale,
+    #[msg("Not Allowlisted")]
+    NotAllowlisted,
+    #[msg("Presale Limit Exceeded")]
+    PresaleLimitExceeded,
+    #[msg("Invalid Soft Cap")]
+    InvalidSoftCap,
+    #[msg("Bonding Curve Failed")]
+    BondingCurveFailed,
+    #[msg("Bonding Curve Not Failed")]
+    BondingCurveNotFailed,
+    #[msg("Missing User Deposit")]
+    MissingUserDeposit,
+    #[msg("Invalid Quote Account")]
+    InvalidQuoteAccount,
+    #[msg("Quote Mint Not Supported")]
+    QuoteMintNotSupported,
+    #[msg("Invalid Launch Params")]
+    InvalidLaunchParams,
+    #[msg("Invalid Launch Config")]
+    InvalidLaunchConfig,
+    #[msg("Invalid Transfer Fee")]
+    InvalidTransferFee,
+    #[msg("No Withheld Fees To Withdraw")]
+    NoWithheldFeesToWithdraw,
+    #[msg("Name Too Long")]
+    NameTooLong,
+    #[msg("Symbol Too Long")]
+    SymbolTooLong,
+    #[msg("Uri Too Long")]
+    UriTooLong,
+    #[msg("Invalid Additional Metadata")]
+    InvalidAdditionalMetadata,
+    #[msg("Invalid Legacy Global")]
+    InvalidLegacyGlobal,
+    #[msg("No Referral Fees To Claim")]
+    NoReferralFeesToClaim,
+    #[msg("Bonding Curve Migrated")]
+    BondingCurveMigrated,
+    #[msg("Invalid Legacy User Transfer Data")]
+    InvalidLegacyUserTransferData,
+    #[msg("Math Overflow")]
+    MathOverflow,
+    #[msg("Not Transferring")]
+    NotTransferring,
+    #[msg("User Transfer Data Not Initialized")]
+    UserTransferDataNotInitialized,
+    #[msg("Bonding Curve Not Migrated")]
+    BondingCurveNotMigrated,
+    #[msg("Unexpected User Deposit")]
+    UnexpectedUserDeposit,
 }
\ No newline at end of file
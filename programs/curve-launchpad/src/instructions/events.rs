@@ -1,5 +1,10 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    state::{GraduationTarget, LaunchFeeSchedule, LaunchParams, LaunchParamsLimits, TransferFeeParams},
+    MetadataField,
+};
+
 #[event]
 pub struct CreateEvent {
     pub name: String,
@@ -8,6 +13,27 @@ pub struct CreateEvent {
     pub mint: Pubkey,
     pub bonding_curve: Pubkey,
     pub creator: Pubkey,
+    /// `Pubkey::default()` for curves priced in SOL.
+    pub quote_mint: Pubkey,
+}
+
+#[event]
+pub struct ClaimVestedEvent {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub vesting: Pubkey,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RefundEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub timestamp: i64,
 }
 
 #[event]
@@ -22,6 +48,12 @@ pub struct TradeEvent {
     pub virtual_token_reserves: u64,
     pub real_sol_reserves: u64,
     pub real_token_reserves: u64,
+    pub fee: u64,
+    /// Effective fee of the trade, elevated right after launch.
+    pub fee_basis_points: u64,
+    pub creator_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
 }
 
 #[event]
@@ -30,6 +62,122 @@ pub struct CompleteEvent {
     pub mint: Pubkey,
     pub bonding_curve: Pubkey,
     pub timestamp: i64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrateEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub whirlpool: Pubkey,
+    pub position: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub liquidity: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClaimCreatorFeesEvent {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClaimReferralFeesEvent {
+    pub referrer: Pubkey,
+    pub sol_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpdateMetadataEvent {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+    pub additional_metadata: Vec<MetadataField>,
+}
+
+#[event]
+pub struct RevokeMetadataAuthorityEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawWithheldEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub creator_vault: Pubkey,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposeAuthorityEvent {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AcceptAuthorityEvent {
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct SetGuardianEvent {
+    pub authority: Pubkey,
+    pub guardian: Pubkey,
+}
+
+#[event]
+pub struct SetLaunchConfigEvent {
+    pub launch_config: Pubkey,
+    pub index: u16,
+    pub fee_recipient: Pubkey,
+    pub fee_basis_points: u64,
+    pub launch_params: LaunchParams,
+    pub launch_fee_schedule: LaunchFeeSchedule,
+    pub quote_mint: Pubkey,
+    pub graduation_target: GraduationTarget,
+}
+
+#[event]
+pub struct SetPausedEvent {
+    pub guardian: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SetFrozenEvent {
+    pub guardian: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub frozen: bool,
+    pub timestamp: i64,
 }
 
 #[event]
@@ -41,4 +189,11 @@ pub struct SetParamsEvent {
     pub initial_real_token_reserves: u64,
     pub initial_token_supply: u64,
     pub fee_basis_points: u64,
+    pub creator_fee_share_basis_points: u64,
+    pub referral_fee_share_basis_points: u64,
+    pub launch_fee_schedule: LaunchFeeSchedule,
+    pub graduation_target: GraduationTarget,
+    pub launch_params_limits: LaunchParamsLimits,
+    pub max_transfer_fee: TransferFeeParams,
+    pub max_launch_fee_basis_points: u64,
 }
\ No newline at end of file
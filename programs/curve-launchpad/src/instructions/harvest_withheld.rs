@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022_extensions::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint},
+    token_interface::{Mint, TokenInterface},
+};
+
+use crate::state::BondingCurve;
+
+#[derive(Accounts)]
+pub struct HarvestWithheld<'info> {
+    #[account(mut)]
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    token_program: Interface<'info, TokenInterface>,
+}
+
+/// Moves the transfer fees withheld in the token accounts passed as remaining accounts into the mint,
+/// from where `withdraw_withheld` pays them to the creator vault.
+pub fn harvest_withheld<'info>(ctx: Context<'_, '_, '_, 'info, HarvestWithheld<'info>>) -> Result<()> {
+    harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            HarvestWithheldTokensToMint {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        ctx.remaining_accounts.to_vec(),
+    )
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::UserTransferData;
+
+/// Creates the transfer data of a wallet that only received tokens, so the transfer hook can
+/// rate limit its transfers. Wallets that traded against the curve already have one.
+#[derive(Accounts)]
+pub struct InitUserTransferData<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    /// CHECK: only used to derive the user transfer data address
+    user: UncheckedAccount<'info>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + UserTransferData::INIT_SPACE,
+        seeds = [b"user", user.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    user_transfer_data: Box<Account<'info, UserTransferData>>,
+
+    system_program: Program<'info, System>,
+}
+
+pub fn init_user_transfer_data(ctx: Context<InitUserTransferData>) -> Result<()> {
+    msg!("Initialized user transfer data {}", ctx.accounts.user_transfer_data.key());
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{state::Global, CurveLaunchpadError, DEFAULT_TOKEN_SUPPLY};
+use crate::{state::{Global, LegacyGlobal}, CurveLaunchpadError, DEFAULT_TOKEN_SUPPLY};
 use anchor_lang::prelude::*;
 
 
@@ -36,6 +36,7 @@ pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
     global.initial_virtual_sol_reserves = 30_000_000_000;
     global.initial_virtual_token_reserves = 1_073_000_000_000_000;
     global.fee_basis_points = 50;
+    global.set_appended_defaults(LegacyGlobal::INIT_SPACE);
 
     msg!("Initialized global state");
 
@@ -0,0 +1,439 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    memo::Memo,
+    token::{self as spl_token, CloseAccount, SyncNative, Token},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use std::str::FromStr;
+use whirlpool::{
+    cpi::accounts::{InitializePoolV2, InitializeTickArray, ModifyLiquidityV2, OpenPosition},
+    math::sqrt_price_from_tick_index,
+    state::{FeeTier, OpenPositionBumps, Whirlpool, WhirlpoolsConfig, MAX_TICK_INDEX, TICK_ARRAY_SIZE},
+    util::remaining_accounts_utils::{AccountsType, RemainingAccountsInfo, RemainingAccountsSlice},
+};
+
+use crate::{
+    liquidity_for_amounts, price_impact_bps, sqrt_price_x64, transfer_fee,
+    state::{BondingCurve, Global},
+    CurveLaunchpadError, MigrateEvent, WHIRLPOOL_MAX_SQRT_PRICE_DEVIATION_BPS, WHIRLPOOL_TICK_SPACING,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateToWhirlpool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        address = bonding_curve.token_account,
+    )]
+    pub bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = bonding_curve.pool_quote_mint() @ CurveLaunchpadError::InvalidQuoteAccount)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// the quote vault of curves priced in a quote mint, else a wrapped SOL account closed after the deposit
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = bonding_curve,
+        associated_token::token_program = quote_token_program,
+    )]
+    pub bonding_curve_quote_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = Pubkey::from_str("J5T5RStZBW2ayuTp5dGCQMHsUApCReRbytDMRd4ZP2aR").unwrap())]
+    pub whirlpools_config: Box<Account<'info, WhirlpoolsConfig>>,
+
+    #[account(has_one = whirlpools_config, constraint = fee_tier.tick_spacing == WHIRLPOOL_TICK_SPACING)]
+    pub fee_tier: Box<Account<'info, FeeTier>>,
+
+    /// CHECK: initialized here unless someone created the pool first, validated by the whirlpool program
+    #[account(mut)]
+    pub whirlpool: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the whirlpool program
+    pub token_badge_a: UncheckedAccount<'info>,
+    /// CHECK: validated by the whirlpool program
+    pub token_badge_b: UncheckedAccount<'info>,
+
+    /// CHECK: new keypair signing when the pool is initialized here, else the vault of the existing pool
+    #[account(mut)]
+    pub token_vault_a: UncheckedAccount<'info>,
+    /// CHECK: new keypair signing when the pool is initialized here, else the vault of the existing pool
+    #[account(mut)]
+    pub token_vault_b: UncheckedAccount<'info>,
+
+    /// CHECK: initialized by the whirlpool program if it does not exist yet
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+    /// CHECK: initialized by the whirlpool program if it does not exist yet
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// CHECK: initialized and validated by the whirlpool program
+    #[account(mut)]
+    pub position: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub position_mint: Signer<'info>,
+
+    /// CHECK: initialized by the whirlpool program, owned by the bonding curve so the liquidity stays locked
+    #[account(mut)]
+    pub position_token_account: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub quote_token_program: Interface<'info, TokenInterface>,
+
+    /// owns the position mint
+    pub position_token_program: Program<'info, Token>,
+
+    pub memo_program: Program<'info, Memo>,
+
+    pub whirlpool_program: Program<'info, whirlpool::program::Whirlpool>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn migrate_to_whirlpool<'info>(ctx: Context<'_, '_, '_, 'info, MigrateToWhirlpool<'info>>) -> Result<()> {
+    //confirm program is initialized
+    require!(
+        ctx.accounts.global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //program is not paused
+    require!(
+        !ctx.accounts.global.paused,
+        CurveLaunchpadError::ProgramPaused,
+    );
+
+    //bonding curve is not frozen
+    require!(
+        !ctx.accounts.bonding_curve.frozen,
+        CurveLaunchpadError::BondingCurveFrozen,
+    );
+
+    //bonding curve must be complete
+    require!(
+        ctx.accounts.bonding_curve.complete,
+        CurveLaunchpadError::BondingCurveNotComplete,
+    );
+
+    //bonding curve is only migrated once
+    require!(
+        !ctx.accounts.bonding_curve.migrated,
+        CurveLaunchpadError::BondingCurveMigrated,
+    );
+
+    let sol_amount = ctx.accounts.bonding_curve.real_sol_reserves;
+    let token_amount = ctx.accounts.bonding_curve_token_account.amount;
+    require!(
+        sol_amount > 0 && token_amount > 0,
+        CurveLaunchpadError::InsufficientLiquidity,
+    );
+
+    //whirlpools order their mints by key
+    let mint_is_a = ctx.accounts.mint.key() < ctx.accounts.quote_mint.key();
+
+    //the pool opens at the final price of the curve
+    let spot_price = ctx
+        .accounts
+        .bonding_curve
+        .curve()
+        .spot_price()
+        .ok_or(CurveLaunchpadError::InvalidPoolPrice)?;
+    let sqrt_price = if mint_is_a {
+        sqrt_price_x64(spot_price, 1 << 64)
+    } else {
+        sqrt_price_x64(1 << 64, spot_price)
+    }
+    .ok_or(CurveLaunchpadError::InvalidPoolPrice)?;
+
+    //wrap the SOL reserves, the reserves of a quote mint are already in the quote vault
+    let is_native_quote = ctx.accounts.bonding_curve.is_native_quote();
+    if is_native_quote {
+        let from_account = &ctx.accounts.bonding_curve;
+        let to_account = &ctx.accounts.bonding_curve_quote_account;
+
+        **from_account.to_account_info().try_borrow_mut_lamports()? -= sol_amount;
+        **to_account.to_account_info().try_borrow_mut_lamports()? += sol_amount;
+
+        spl_token::sync_native(CpiContext::new(
+            ctx.accounts.quote_token_program.to_account_info(),
+            SyncNative {
+                account: ctx.accounts.bonding_curve_quote_account.to_account_info(),
+            },
+        ))?;
+        ctx.accounts.bonding_curve_quote_account.reload()?;
+    }
+    let quote_amount_before = ctx.accounts.bonding_curve_quote_account.amount;
+
+    let (token_mint_a, token_mint_b, token_program_a, token_program_b, token_owner_account_a, token_owner_account_b) = if mint_is_a {
+        (
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.quote_mint.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.quote_token_program.to_account_info(),
+            ctx.accounts.bonding_curve_token_account.to_account_info(),
+            ctx.accounts.bonding_curve_quote_account.to_account_info(),
+        )
+    } else {
+        (
+            ctx.accounts.quote_mint.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.quote_token_program.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.bonding_curve_quote_account.to_account_info(),
+            ctx.accounts.bonding_curve_token_account.to_account_info(),
+        )
+    };
+    //the pool receives the tokens net of the transfer fee, the gross amount is the most it may take
+    let net_token_amount = token_amount - transfer_fee(&ctx.accounts.mint.to_account_info(), token_amount)?;
+    let (amount_a, amount_b) = if mint_is_a {
+        (token_amount, sol_amount)
+    } else {
+        (sol_amount, token_amount)
+    };
+    let (liquidity_amount_a, liquidity_amount_b) = if mint_is_a {
+        (net_token_amount, sol_amount)
+    } else {
+        (sol_amount, net_token_amount)
+    };
+
+    let whirlpool_program = ctx.accounts.whirlpool_program.to_account_info();
+
+    //the pool of the pinned config, mints and tick spacing
+    let (whirlpool_address, _) = Pubkey::find_program_address(
+        &[
+            b"whirlpool",
+            ctx.accounts.whirlpools_config.key().as_ref(),
+            token_mint_a.key.as_ref(),
+            token_mint_b.key.as_ref(),
+            &WHIRLPOOL_TICK_SPACING.to_le_bytes(),
+        ],
+        whirlpool_program.key,
+    );
+    require!(
+        ctx.accounts.whirlpool.key() == whirlpool_address,
+        ErrorCode::ConstraintSeeds,
+    );
+
+    if ctx.accounts.whirlpool.data_is_empty() {
+        //the vaults are created with the pool
+        require!(
+            ctx.accounts.token_vault_a.is_signer && ctx.accounts.token_vault_b.is_signer,
+            ErrorCode::AccountNotSigner,
+        );
+
+        whirlpool::cpi::initialize_pool_v2(
+            CpiContext::new(
+                whirlpool_program.clone(),
+                InitializePoolV2 {
+                    whirlpools_config: ctx.accounts.whirlpools_config.to_account_info(),
+                    token_mint_a: token_mint_a.clone(),
+                    token_mint_b: token_mint_b.clone(),
+                    token_badge_a: ctx.accounts.token_badge_a.to_account_info(),
+                    token_badge_b: ctx.accounts.token_badge_b.to_account_info(),
+                    funder: ctx.accounts.payer.to_account_info(),
+                    whirlpool: ctx.accounts.whirlpool.to_account_info(),
+                    token_vault_a: ctx.accounts.token_vault_a.to_account_info(),
+                    token_vault_b: ctx.accounts.token_vault_b.to_account_info(),
+                    fee_tier: ctx.accounts.fee_tier.to_account_info(),
+                    token_program_a: token_program_a.clone(),
+                    token_program_b: token_program_b.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+            ),
+            WHIRLPOOL_TICK_SPACING,
+            sqrt_price,
+        )?;
+    } else {
+        //anyone can create the pool first, it is only used if it trades at the curve price
+        require!(
+            ctx.accounts.whirlpool.owner == whirlpool_program.key,
+            ErrorCode::ConstraintOwner,
+        );
+        let whirlpool = Whirlpool::try_deserialize(&mut &ctx.accounts.whirlpool.try_borrow_data()?[..])?;
+        require!(
+            price_impact_bps(sqrt_price, whirlpool.sqrt_price) <= WHIRLPOOL_MAX_SQRT_PRICE_DEVIATION_BPS,
+            CurveLaunchpadError::InvalidPoolPrice,
+        );
+    }
+
+    //full range position
+    let tick_spacing = WHIRLPOOL_TICK_SPACING as i32;
+    let tick_upper_index = MAX_TICK_INDEX / tick_spacing * tick_spacing;
+    let tick_lower_index = -tick_upper_index;
+
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing;
+    for (tick_array, tick_index) in [
+        (&ctx.accounts.tick_array_lower, tick_lower_index),
+        (&ctx.accounts.tick_array_upper, tick_upper_index),
+    ] {
+        if !tick_array.data_is_empty() {
+            continue;
+        }
+
+        whirlpool::cpi::initialize_tick_array(
+            CpiContext::new(
+                whirlpool_program.clone(),
+                InitializeTickArray {
+                    whirlpool: ctx.accounts.whirlpool.to_account_info(),
+                    funder: ctx.accounts.payer.to_account_info(),
+                    tick_array: tick_array.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                },
+            ),
+            tick_index.div_euclid(ticks_in_array) * ticks_in_array,
+        )?;
+    }
+
+    let (_, position_bump) = Pubkey::find_program_address(
+        &[b"position", ctx.accounts.position_mint.key().as_ref()],
+        ctx.accounts.whirlpool_program.key,
+    );
+
+    whirlpool::cpi::open_position(
+        CpiContext::new(
+            whirlpool_program.clone(),
+            OpenPosition {
+                funder: ctx.accounts.payer.to_account_info(),
+                owner: ctx.accounts.bonding_curve.to_account_info(),
+                position: ctx.accounts.position.to_account_info(),
+                position_mint: ctx.accounts.position_mint.to_account_info(),
+                position_token_account: ctx.accounts.position_token_account.to_account_info(),
+                whirlpool: ctx.accounts.whirlpool.to_account_info(),
+                token_program: ctx.accounts.position_token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+                associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            },
+        ),
+        OpenPositionBumps { position_bump },
+        tick_lower_index,
+        tick_upper_index,
+    )?;
+
+    let liquidity = liquidity_for_amounts(
+        sqrt_price,
+        sqrt_price_from_tick_index(tick_lower_index),
+        sqrt_price_from_tick_index(tick_upper_index),
+        liquidity_amount_a,
+        liquidity_amount_b,
+    )
+    .ok_or(CurveLaunchpadError::InvalidPoolPrice)?;
+    require!(liquidity > 0, CurveLaunchpadError::InsufficientLiquidity);
+
+    let signer: [&[&[u8]]; 1] = [&[
+        BondingCurve::SEED_PREFIX,
+        ctx.accounts.bonding_curve.mint.as_ref(),
+        &[ctx.bumps.bonding_curve],
+    ]];
+
+    //the accounts of the mint's transfer hook, forwarded to the pool
+    let remaining_accounts_info = (!ctx.remaining_accounts.is_empty()).then(|| RemainingAccountsInfo {
+        slices: vec![RemainingAccountsSlice {
+            accounts_type: if mint_is_a {
+                AccountsType::TransferHookA
+            } else {
+                AccountsType::TransferHookB
+            },
+            length: ctx.remaining_accounts.len() as u8,
+        }],
+    });
+
+    whirlpool::cpi::increase_liquidity_v2(
+        CpiContext::new_with_signer(
+            whirlpool_program,
+            ModifyLiquidityV2 {
+                whirlpool: ctx.accounts.whirlpool.to_account_info(),
+                token_program_a,
+                token_program_b,
+                memo_program: ctx.accounts.memo_program.to_account_info(),
+                position_authority: ctx.accounts.bonding_curve.to_account_info(),
+                position: ctx.accounts.position.to_account_info(),
+                position_token_account: ctx.accounts.position_token_account.to_account_info(),
+                token_mint_a,
+                token_mint_b,
+                token_owner_account_a,
+                token_owner_account_b,
+                token_vault_a: ctx.accounts.token_vault_a.to_account_info(),
+                token_vault_b: ctx.accounts.token_vault_b.to_account_info(),
+                tick_array_lower: ctx.accounts.tick_array_lower.to_account_info(),
+                tick_array_upper: ctx.accounts.tick_array_upper.to_account_info(),
+            },
+            &signer,
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        liquidity,
+        amount_a,
+        amount_b,
+        remaining_accounts_info,
+    )?;
+
+    ctx.accounts.bonding_curve_quote_account.reload()?;
+    ctx.accounts.bonding_curve_token_account.reload()?;
+    let deposited_sol_amount = quote_amount_before - ctx.accounts.bonding_curve_quote_account.amount;
+    let deposited_token_amount = token_amount - ctx.accounts.bonding_curve_token_account.amount;
+
+    //unwrap whatever SOL the pool did not take back into the bonding curve, a quote mint stays in the quote vault
+    let remaining_sol = if is_native_quote {
+        let remaining_sol = ctx.accounts.bonding_curve_quote_account.amount;
+
+        spl_token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.quote_token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.bonding_curve_quote_account.to_account_info(),
+                destination: ctx.accounts.bonding_curve.to_account_info(),
+                authority: ctx.accounts.bonding_curve.to_account_info(),
+            },
+            &signer,
+        ))?;
+
+        remaining_sol
+    } else {
+        sol_amount - deposited_sol_amount
+    };
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.real_sol_reserves = remaining_sol;
+    bonding_curve.migrated = true;
+
+    emit_cpi!(MigrateEvent {
+        user: *ctx.accounts.payer.to_account_info().key,
+        mint: *ctx.accounts.mint.to_account_info().key,
+        bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
+        whirlpool: *ctx.accounts.whirlpool.to_account_info().key,
+        position: *ctx.accounts.position.to_account_info().key,
+        sol_amount: deposited_sol_amount,
+        token_amount: deposited_token_amount,
+        liquidity,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+use crate::{
+    state::{Global, LegacyGlobal},
+    CurveLaunchpadError,
+};
+
+/// Grows a global account created before fields were appended to `Global`
+/// and writes defaults for the new fields.
+#[derive(Accounts)]
+pub struct MigrateGlobal<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    /// CHECK: deserialized as a LegacyGlobal in the handler
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX],
+        bump,
+        owner = crate::ID,
+    )]
+    global: UncheckedAccount<'info>,
+
+    system_program: Program<'info, System>,
+}
+
+pub fn migrate_global(ctx: Context<MigrateGlobal>) -> Result<()> {
+    let (legacy, legacy_len) = {
+        let data = ctx.accounts.global.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data.len() < 8 + Global::INIT_SPACE && data[..8] == Global::DISCRIMINATOR,
+            CurveLaunchpadError::InvalidLegacyGlobal,
+        );
+        (LegacyGlobal::deserialize(&mut &data[8..])?, data.len())
+    };
+
+    //confirm program is initialized
+    require!(
+        legacy.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //confirm user is the authority
+    require!(
+        legacy.authority == *ctx.accounts.authority.to_account_info().key,
+        CurveLaunchpadError::InvalidAuthority
+    );
+
+    //top up rent for the new size, paid by the authority
+    let global = ctx.accounts.global.to_account_info();
+    let space = 8 + Global::INIT_SPACE;
+    let rent_lamports = Rent::get()?.minimum_balance(space).saturating_sub(global.lamports());
+    if rent_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: global.clone(),
+                },
+            ),
+            rent_lamports,
+        )?;
+    }
+    global.realloc(space, true)?;
+
+    //fields the account had no room for get the same defaults as at initialize, the rest keep
+    //their settings. The grown space is zeroed, so those fields read as zero until then
+    let mut migrated = Global::deserialize(&mut &global.try_borrow_data()?[8..])?;
+    migrated.set_appended_defaults(legacy_len - 8);
+    migrated.try_serialize(&mut &mut global.try_borrow_mut_data()?[..])?;
+
+    msg!("Migrated global state");
+
+    Ok(())
+}
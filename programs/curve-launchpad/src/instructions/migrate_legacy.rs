@@ -0,0 +1,154 @@
+use anchor_lang::{prelude::*, system_program, Discriminator};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    state::{BondingCurve, Global, LaunchFeeSchedule, LegacyBondingCurve, RateLimitPolicy},
+    transfer_checked_with_hook, CurveLaunchpadError,
+};
+
+/// Moves a bonding curve created under the old `[SEED_PREFIX, creator]` seeds
+/// to its mint-seeded address, together with its SOL and tokens.
+#[derive(Accounts)]
+pub struct MigrateLegacyBondingCurve<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: only used to derive the legacy bonding curve address
+    creator: UncheckedAccount<'info>,
+
+    /// CHECK: deserialized as a LegacyBondingCurve in the handler
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX, creator.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    legacy_bonding_curve: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = legacy_bonding_curve,
+    )]
+    legacy_bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+        space = 8 + BondingCurve::INIT_SPACE,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve,
+        associated_token::token_program = token_program,
+    )]
+    bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    system_program: Program<'info, System>,
+
+    token_program: Interface<'info, TokenInterface>,
+
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn migrate_legacy_bonding_curve<'info>(ctx: Context<'_, '_, '_, 'info, MigrateLegacyBondingCurve<'info>>) -> Result<()> {
+    //confirm program is initialized
+    require!(
+        ctx.accounts.global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //program is not paused
+    require!(
+        !ctx.accounts.global.paused,
+        CurveLaunchpadError::ProgramPaused,
+    );
+
+    let legacy = {
+        let data = ctx.accounts.legacy_bonding_curve.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data.len() < 8 + BondingCurve::INIT_SPACE && data[..8] == BondingCurve::DISCRIMINATOR,
+            CurveLaunchpadError::InvalidLegacyBondingCurve,
+        );
+        LegacyBondingCurve::deserialize(&mut &data[8..])?
+    };
+
+    require!(
+        legacy.token_account == ctx.accounts.legacy_bonding_curve_token_account.key(),
+        CurveLaunchpadError::InvalidLegacyBondingCurve,
+    );
+
+    //move the tokens to the new bonding curve token account
+    let token_amount = ctx.accounts.legacy_bonding_curve_token_account.amount;
+
+    let signer: [&[&[u8]]; 1] = [&[
+        BondingCurve::SEED_PREFIX,
+        legacy.creator.as_ref(),
+        &[ctx.bumps.legacy_bonding_curve],
+    ]];
+
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.legacy_bonding_curve_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.bonding_curve_token_account.to_account_info(),
+        ctx.accounts.legacy_bonding_curve.to_account_info(),
+        ctx.remaining_accounts,
+        token_amount,
+        ctx.accounts.mint.decimals,
+        &signer,
+    )?;
+
+    //move the SOL reserves, the legacy rent goes back to the payer
+    let legacy_bonding_curve = ctx.accounts.legacy_bonding_curve.to_account_info();
+    let legacy_lamports = legacy_bonding_curve.lamports();
+    let rent_lamports = legacy_lamports
+        .checked_sub(legacy.real_sol_reserves)
+        .ok_or(CurveLaunchpadError::InvalidLegacyBondingCurve)?;
+
+    **legacy_bonding_curve.try_borrow_mut_lamports()? = 0;
+    **ctx.accounts.bonding_curve.to_account_info().try_borrow_mut_lamports()? += legacy.real_sol_reserves;
+    **ctx.accounts.payer.try_borrow_mut_lamports()? += rent_lamports;
+
+    //close the legacy bonding curve
+    legacy_bonding_curve.realloc(0, false)?;
+    legacy_bonding_curve.assign(&system_program::ID);
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.virtual_sol_reserves = legacy.virtual_sol_reserves;
+    bonding_curve.virtual_token_reserves = legacy.virtual_token_reserves;
+    bonding_curve.real_sol_reserves = legacy.real_sol_reserves;
+    bonding_curve.real_token_reserves = legacy.real_token_reserves;
+    bonding_curve.token_total_supply = legacy.token_total_supply;
+    bonding_curve.complete = legacy.complete;
+    bonding_curve.creator = legacy.creator;
+    bonding_curve.team = legacy.team;
+    bonding_curve.token_account = *ctx.accounts.bonding_curve_token_account.to_account_info().key;
+    bonding_curve.mint = *ctx.accounts.mint.to_account_info().key;
+    bonding_curve.rate_limit_policy = RateLimitPolicy::default();
+    //legacy curves launched long ago, so they only pay the base fee
+    bonding_curve.launch_fee_schedule = LaunchFeeSchedule::default();
+    //best guess at the params the legacy curve launched with
+    bonding_curve.launch_params = ctx.accounts.global.launch_params();
+
+    msg!("Migrated legacy bonding curve {}", ctx.accounts.legacy_bonding_curve.key());
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anchor_lang::{prelude::*, system_program, Discriminator};
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    state::{LegacyUserTransferData, UserTransferData},
+    CurveLaunchpadError,
+};
+
+/// Grows user transfer data created before the bucket and presale fields were
+/// appended to `UserTransferData`, so buys, sells and the transfer hook can read it again.
+#[derive(Accounts)]
+pub struct MigrateUserTransferData<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    /// CHECK: only used to derive the user transfer data address
+    user: UncheckedAccount<'info>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: deserialized as a LegacyUserTransferData in the handler
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref(), mint.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    user_transfer_data: UncheckedAccount<'info>,
+
+    system_program: Program<'info, System>,
+}
+
+pub fn migrate_user_transfer_data(ctx: Context<MigrateUserTransferData>) -> Result<()> {
+    let legacy = {
+        let data = ctx.accounts.user_transfer_data.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data.len() < 8 + UserTransferData::INIT_SPACE && data[..8] == UserTransferData::DISCRIMINATOR,
+            CurveLaunchpadError::InvalidLegacyUserTransferData,
+        );
+        LegacyUserTransferData::deserialize(&mut &data[8..])?
+    };
+
+    //top up rent for the new size, paid by the payer
+    let user_transfer_data = ctx.accounts.user_transfer_data.to_account_info();
+    let space = 8 + UserTransferData::INIT_SPACE;
+    let rent_lamports = Rent::get()?.minimum_balance(space).saturating_sub(user_transfer_data.lamports());
+    if rent_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: user_transfer_data.clone(),
+                },
+            ),
+            rent_lamports,
+        )?;
+    }
+    user_transfer_data.realloc(space, true)?;
+
+    //the bucket refills from the last transfer, as the old cooldown did
+    let migrated = UserTransferData {
+        last_transfer_timestamp: legacy.last_transfer_timestamp,
+        available_amount: 0,
+        presale_purchased_amount: 0,
+    };
+    migrated.try_serialize(&mut &mut user_transfer_data.try_borrow_mut_data()?[..])?;
+
+    msg!("Migrated user transfer data {}", ctx.accounts.user_transfer_data.key());
+
+    Ok(())
+}
@@ -8,6 +8,27 @@ pub mod constants;
 pub mod events;
 pub mod util;
 pub mod withdraw;
+pub mod migrate;
+pub mod migrate_legacy;
+pub mod migrate_global;
+pub mod migrate_user_transfer_data;
+pub mod init_user_transfer_data;
+pub mod quote;
+pub mod claim_creator_fees;
+pub mod claim_referral_fees;
+pub mod propose_authority;
+pub mod accept_authority;
+pub mod set_guardian;
+pub mod set_paused;
+pub mod set_frozen;
+pub mod transfer_hook;
+pub mod claim_vested;
+pub mod refund;
+pub mod set_launch_config;
+pub mod harvest_withheld;
+pub mod withdraw_withheld;
+pub mod update_metadata;
+pub mod revoke_metadata_authority;
 
 pub use initialize::*;
 pub use errors::*;
@@ -18,4 +39,25 @@ pub use set_params::*;
 pub use constants::*;
 pub use events::*;
 pub use util::*;
-pub use withdraw::*;
\ No newline at end of file
+pub use withdraw::*;
+pub use migrate::*;
+pub use migrate_legacy::*;
+pub use migrate_global::*;
+pub use migrate_user_transfer_data::*;
+pub use init_user_transfer_data::*;
+pub use quote::*;
+pub use claim_creator_fees::*;
+pub use claim_referral_fees::*;
+pub use propose_authority::*;
+pub use accept_authority::*;
+pub use set_guardian::*;
+pub use set_paused::*;
+pub use set_frozen::*;
+pub use transfer_hook::*;
+pub use claim_vested::*;
+pub use refund::*;
+pub use set_launch_config::*;
+pub use harvest_withheld::*;
+pub use withdraw_withheld::*;
+pub use update_metadata::*;
+pub use revoke_metadata_authority::*;
\ No newline at end of file
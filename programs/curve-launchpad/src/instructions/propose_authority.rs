@@ -0,0 +1,41 @@
+use crate::{state::Global, CurveLaunchpadError, ProposeAuthorityEvent};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    user: Signer<'info>,
+}
+
+pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    let global = &mut ctx.accounts.global;
+
+    //confirm program is initialized
+    require!(
+        global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //confirm user is the authority
+    require!(
+        global.authority == *ctx.accounts.user.to_account_info().key,
+        CurveLaunchpadError::InvalidAuthority
+    );
+
+    //the authority only changes once the new authority accepts
+    global.pending_authority = Some(new_authority);
+
+    emit_cpi!(ProposeAuthorityEvent {
+        authority: global.authority,
+        pending_authority: new_authority,
+    });
+
+    Ok(())
+}
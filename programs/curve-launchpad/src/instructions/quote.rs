@@ -0,0 +1,185 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::{
+    calculate_fee, fee_config, graduation_target, price_impact_bps, transfer_fee,
+    state::{BondingCurve, Global, LaunchConfig},
+    CurveLaunchpadError,
+};
+
+/// Read-only view of a bonding curve, used to simulate trades.
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(address = bonding_curve.token_account)]
+    bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //only for curves created under a launch config
+    #[account(address = bonding_curve.launch_config @ CurveLaunchpadError::InvalidLaunchConfig)]
+    launch_config: Option<Box<Account<'info, LaunchConfig>>>,
+}
+
+/// `BuyResult` of a simulated buy, with the fee on top and the reserves after the trade.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BuyQuote {
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub fee: u64,
+    pub fee_basis_points: u64,
+    /// Token-2022 fee withheld from the tokens moved by the trade.
+    pub transfer_fee: u64,
+    pub price_impact_bps: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+}
+
+/// `SellResult` of a simulated sell, with the fee taken out and the reserves after the trade.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SellQuote {
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub fee: u64,
+    pub fee_basis_points: u64,
+    /// Token-2022 fee withheld from the tokens moved by the trade.
+    pub transfer_fee: u64,
+    pub price_impact_bps: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+}
+
+pub fn quote_buy(ctx: Context<Quote>, token_amount: u64) -> Result<BuyQuote> {
+    require!(
+        ctx.accounts.global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //bonding curve is not complete
+    require!(
+        !ctx.accounts.bonding_curve.complete,
+        CurveLaunchpadError::BondingCurveComplete,
+    );
+
+    //bonding curve did not miss its soft cap
+    require!(
+        !ctx.accounts.bonding_curve.soft_cap_failed(Clock::get()?.unix_timestamp),
+        CurveLaunchpadError::BondingCurveFailed,
+    );
+
+    require!(token_amount > 0, CurveLaunchpadError::MinBuy,);
+
+    //the buy is clamped to the tokens the curve holds and to land on the graduation target, as in `buy`
+    let token_amount = token_amount.min(ctx.accounts.bonding_curve_token_account.amount);
+
+    let mut curve = ctx.accounts.bonding_curve.curve();
+
+    let target = graduation_target(&ctx.accounts.global, &ctx.accounts.bonding_curve, &ctx.accounts.launch_config)?;
+    let token_amount = ctx
+        .accounts
+        .bonding_curve
+        .tokens_to_graduate(curve.as_ref(), target, token_amount)
+        .unwrap_or(token_amount);
+
+    let price_before = curve.spot_price().ok_or(CurveLaunchpadError::InsufficientTokens)?;
+    let buy_result = curve
+        .apply_buy(token_amount as u128)
+        .ok_or(CurveLaunchpadError::InsufficientTokens)?;
+    let price_after = curve.spot_price().ok_or(CurveLaunchpadError::InsufficientTokens)?;
+
+    let reserves = curve.reserves();
+    let (_, base_fee_basis_points) = fee_config(
+        &ctx.accounts.global,
+        &ctx.accounts.bonding_curve,
+        &ctx.accounts.launch_config,
+    )?;
+    let fee_basis_points = ctx
+        .accounts
+        .bonding_curve
+        .fee_basis_points(base_fee_basis_points, &Clock::get()?);
+
+    Ok(BuyQuote {
+        token_amount: buy_result.token_amount,
+        sol_amount: buy_result.sol_amount,
+        fee: calculate_fee(buy_result.sol_amount, fee_basis_points),
+        fee_basis_points,
+        transfer_fee: transfer_fee(&ctx.accounts.mint.to_account_info(), buy_result.token_amount)?,
+        price_impact_bps: price_impact_bps(price_before, price_after),
+        virtual_sol_reserves: reserves.virtual_sol_reserves as u64,
+        virtual_token_reserves: reserves.virtual_token_reserves as u64,
+        real_sol_reserves: reserves.real_sol_reserves as u64,
+        real_token_reserves: reserves.real_token_reserves as u64,
+    })
+}
+
+pub fn quote_sell(ctx: Context<Quote>, token_amount: u64) -> Result<SellQuote> {
+    require!(
+        ctx.accounts.global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //bonding curve is not complete
+    require!(
+        !ctx.accounts.bonding_curve.complete,
+        CurveLaunchpadError::BondingCurveComplete,
+    );
+
+    //bonding curve did not miss its soft cap
+    require!(
+        !ctx.accounts.bonding_curve.soft_cap_failed(Clock::get()?.unix_timestamp),
+        CurveLaunchpadError::BondingCurveFailed,
+    );
+
+    require!(token_amount > 0, CurveLaunchpadError::MinSell,);
+
+    let mut curve = ctx.accounts.bonding_curve.curve();
+
+    //the curve only receives the tokens net of the transfer fee
+    let token_transfer_fee = transfer_fee(&ctx.accounts.mint.to_account_info(), token_amount)?;
+    require!(token_amount > token_transfer_fee, CurveLaunchpadError::MinSell);
+
+    let price_before = curve.spot_price().ok_or(CurveLaunchpadError::InsufficientSOL)?;
+    let sell_result = curve
+        .apply_sell((token_amount - token_transfer_fee) as u128)
+        .ok_or(CurveLaunchpadError::InsufficientSOL)?;
+    let price_after = curve.spot_price().ok_or(CurveLaunchpadError::InsufficientSOL)?;
+
+    let reserves = curve.reserves();
+    let (_, base_fee_basis_points) = fee_config(
+        &ctx.accounts.global,
+        &ctx.accounts.bonding_curve,
+        &ctx.accounts.launch_config,
+    )?;
+    let fee_basis_points = ctx
+        .accounts
+        .bonding_curve
+        .fee_basis_points(base_fee_basis_points, &Clock::get()?);
+
+    Ok(SellQuote {
+        token_amount: sell_result.token_amount,
+        sol_amount: sell_result.sol_amount,
+        fee: calculate_fee(sell_result.sol_amount, fee_basis_points),
+        fee_basis_points,
+        transfer_fee: token_transfer_fee,
+        price_impact_bps: price_impact_bps(price_before, price_after),
+        virtual_sol_reserves: reserves.virtual_sol_reserves as u64,
+        virtual_token_reserves: reserves.virtual_token_reserves as u64,
+        real_sol_reserves: reserves.real_sol_reserves as u64,
+        real_token_reserves: reserves.real_token_reserves as u64,
+    })
+}
@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    state::{BondingCurve, UserDeposit},
+    quote_account, quote_accounts, refund_amount, transfer_checked_with_hook, transfer_quote, CurveLaunchpadError, RefundEvent, DEFAULT_DECIMALS,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut)]
+    user: Signer<'info>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        address = bonding_curve.token_account,
+    )]
+    bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+    )]
+    user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [UserDeposit::SEED_PREFIX, mint.key().as_ref(), user.key().as_ref()],
+        bump,
+        has_one = user,
+    )]
+    user_deposit: Box<Account<'info, UserDeposit>>,
+
+    token_program: Interface<'info, TokenInterface>,
+
+    //only for curves priced in an SPL quote mint
+    #[account(address = bonding_curve.quote_mint @ CurveLaunchpadError::InvalidQuoteAccount)]
+    quote_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        address = bonding_curve.quote_vault @ CurveLaunchpadError::InvalidQuoteAccount,
+    )]
+    quote_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    user_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    quote_token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn refund<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>, token_amount: u64) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    //only curves that missed their soft cap refund
+    require!(
+        ctx.accounts.bonding_curve.soft_cap_failed(timestamp),
+        CurveLaunchpadError::BondingCurveNotFailed,
+    );
+
+    require!(token_amount > 0, CurveLaunchpadError::MinSell,);
+
+    //can't return more than was bought from the curve
+    require!(
+        token_amount <= ctx.accounts.user_deposit.token_amount,
+        CurveLaunchpadError::InsufficientTokens,
+    );
+
+    //sells at a profit can leave the deposits above what the curve holds,
+    //then every depositor gets the same share back
+    let deposit_sol_amount = ctx.accounts.user_deposit.record_sell(token_amount);
+    let sol_amount = refund_amount(
+        deposit_sol_amount,
+        ctx.accounts.bonding_curve.real_sol_reserves,
+        ctx.accounts.bonding_curve.deposited_sol_amount,
+    )
+    .ok_or(CurveLaunchpadError::MathOverflow)?;
+
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.bonding_curve_token_account.to_account_info(),
+        ctx.accounts.user.to_account_info(),
+        ctx.remaining_accounts,
+        token_amount,
+        DEFAULT_DECIMALS as u8,
+        &[],
+    )?;
+
+    if ctx.accounts.bonding_curve.is_native_quote() {
+        let bonding_curve = ctx.accounts.bonding_curve.to_account_info();
+        let bonding_curve_lamports = bonding_curve
+            .lamports()
+            .checked_sub(sol_amount)
+            .ok_or(CurveLaunchpadError::InsufficientSOL)?;
+        let user_lamports = ctx
+            .accounts
+            .user
+            .lamports()
+            .checked_add(sol_amount)
+            .ok_or(CurveLaunchpadError::MathOverflow)?;
+
+        **bonding_curve.try_borrow_mut_lamports()? = bonding_curve_lamports;
+        **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? = user_lamports;
+    } else {
+        let (quote_mint, quote_vault, quote_token_program) = quote_accounts(
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.quote_token_program,
+        )?;
+        let user_quote_account = quote_account(&ctx.accounts.user_quote_account, &quote_mint.key(), ctx.accounts.user.key)?;
+
+        let signer: [&[&[u8]]; 1] = [&[
+            BondingCurve::SEED_PREFIX,
+            ctx.accounts.bonding_curve.mint.as_ref(),
+            &[ctx.bumps.bonding_curve],
+        ]];
+
+        transfer_quote(
+            quote_token_program.to_account_info(),
+            quote_vault.to_account_info(),
+            quote_mint,
+            user_quote_account.to_account_info(),
+            ctx.accounts.bonding_curve.to_account_info(),
+            sol_amount,
+            &signer,
+        )?;
+    }
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.failed = true;
+    bonding_curve.real_sol_reserves = bonding_curve
+        .real_sol_reserves
+        .checked_sub(sol_amount)
+        .ok_or(CurveLaunchpadError::InsufficientSOL)?;
+    bonding_curve.deposited_sol_amount = bonding_curve.deposited_sol_amount.saturating_sub(deposit_sol_amount);
+
+    emit_cpi!(RefundEvent {
+        user: *ctx.accounts.user.to_account_info().key,
+        mint: *ctx.accounts.mint.to_account_info().key,
+        bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
+        token_amount,
+        sol_amount,
+        timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022_extensions::{token_metadata_update_authority, TokenMetadataUpdateAuthority},
+    token_interface::{Mint, TokenInterface},
+};
+
+use crate::{state::BondingCurve, CurveLaunchpadError, RevokeMetadataAuthorityEvent};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeMetadataAuthority<'info> {
+    #[account(mut)]
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Using seed to validate mint_authority account, the update authority of the metadata
+    #[account(
+        seeds=[b"mint-authority"],
+        bump,
+    )]
+    mint_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    token_program: Interface<'info, TokenInterface>,
+}
+
+/// Anyone can make the metadata immutable once the curve graduates.
+pub fn revoke_metadata_authority(ctx: Context<RevokeMetadataAuthority>) -> Result<()> {
+    require!(
+        ctx.accounts.bonding_curve.complete,
+        CurveLaunchpadError::BondingCurveNotComplete,
+    );
+
+    let seeds = &["mint-authority".as_bytes(), &[ctx.bumps.mint_authority]];
+    let signer = [&seeds[..]];
+
+    //the default authority is none, `new_authority` is not passed to the token program
+    token_metadata_update_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TokenMetadataUpdateAuthority {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                metadata: ctx.accounts.mint.to_account_info(),
+                current_authority: ctx.accounts.mint_authority.to_account_info(),
+                new_authority: ctx.accounts.mint_authority.to_account_info(),
+            },
+            &signer,
+        ),
+        Default::default(),
+    )?;
+
+    emit_cpi!(RevokeMetadataAuthorityEvent {
+        mint: *ctx.accounts.mint.to_account_info().key,
+        bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,8 +1,8 @@
 use crate::{
-    amm, calculate_fee, check_buy_sell, state::{UserTransferData, BondingCurve, Global}, CurveLaunchpadError, TradeEvent
+    check_buy_sell, fee_config, quote_account, quote_accounts, trade_fees, transfer_fee, amount_with_transfer_fee, transfer_quote, TradeFees, transfer_checked_with_hook, state::{UserTransferData, BondingCurve, CreatorVault, Global, LaunchConfig, ReferrerStats, UserDeposit}, CurveLaunchpadError, TradeEvent
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{self as token, Mint, TokenInterface, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount};
 
 #[event_cpi]
 #[derive(Accounts)]
@@ -24,7 +24,7 @@ pub struct Sell<'info> {
 
     #[account(
         mut,
-        seeds = [BondingCurve::SEED_PREFIX, bonding_curve.creator.as_ref()],
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
         bump,
     )]
     bonding_curve: Box<Account<'info, BondingCurve>>,
@@ -35,10 +35,32 @@ pub struct Sell<'info> {
     )]
     bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + CreatorVault::INIT_SPACE,
+        seeds = [CreatorVault::SEED_PREFIX, bonding_curve.key().as_ref()],
+        bump,
+    )]
+    creator_vault: Box<Account<'info, CreatorVault>>,
+
+    /// Optional referrer, its share of the SOL fee accrues in its stats until claimed
+    referrer: Option<SystemAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ReferrerStats::INIT_SPACE,
+        seeds = [ReferrerStats::SEED_PREFIX, referrer.as_ref().map(|referrer| referrer.key()).unwrap_or_default().as_ref()],
+        bump,
+    )]
+    referrer_stats: Option<Box<Account<'info, ReferrerStats>>>,
+
     #[account(
         mut,
-      //  associated_token::mint = mint,
-      //  associated_token::authority = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
     user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -54,16 +76,53 @@ pub struct Sell<'info> {
         bump
     )]
     pub user_transfer_data: Account<'info, UserTransferData>,
+
+    //required on soft-cap curves, so the refundable deposit follows the sell
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserDeposit::INIT_SPACE,
+        seeds = [UserDeposit::SEED_PREFIX, mint.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub user_deposit: Option<Box<Account<'info, UserDeposit>>>,
+
+    //only for curves priced in an SPL quote mint
+    #[account(address = bonding_curve.quote_mint @ CurveLaunchpadError::InvalidQuoteAccount)]
+    pub quote_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        address = bonding_curve.quote_vault @ CurveLaunchpadError::InvalidQuoteAccount,
+    )]
+    pub quote_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub user_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub fee_recipient_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub creator_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub referrer_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub quote_token_program: Option<Interface<'info, TokenInterface>>,
+
+    //only for curves created under a launch config
+    #[account(address = bonding_curve.launch_config @ CurveLaunchpadError::InvalidLaunchConfig)]
+    pub launch_config: Option<Box<Account<'info, LaunchConfig>>>,
 }
 
-pub fn sell(ctx: Context<Sell>, token_amount: u64, min_sol_output: u64) -> Result<()> {
+pub fn sell<'info>(ctx: Context<'_, '_, '_, 'info, Sell<'info>>, token_amount: u64, min_sol_output: u64) -> Result<()> {
     //check if bonding curve is complete
     // Check if the user is authorized to sell
     check_buy_sell(
         &mut ctx.accounts.user_transfer_data,
         ctx.accounts.user.to_account_info(),
-        ctx.accounts.system_program.to_account_info(),
-        *ctx.accounts.bonding_curve.clone(),
+        &ctx.accounts.bonding_curve,
         token_amount,
     )?;
     require!(
@@ -71,18 +130,60 @@ pub fn sell(ctx: Context<Sell>, token_amount: u64, min_sol_output: u64) -> Resul
         CurveLaunchpadError::BondingCurveComplete,
     );
 
+    //program is not paused
+    require!(
+        !ctx.accounts.global.paused,
+        CurveLaunchpadError::ProgramPaused,
+    );
+
+    //bonding curve is not frozen
+    require!(
+        !ctx.accounts.bonding_curve.frozen,
+        CurveLaunchpadError::BondingCurveFrozen,
+    );
+
+    //bonding curve did not miss its soft cap, holders refund instead
+    require!(
+        !ctx.accounts.bonding_curve.soft_cap_failed(Clock::get()?.unix_timestamp),
+        CurveLaunchpadError::BondingCurveFailed,
+    );
+
+    require!(
+        ctx.accounts.bonding_curve.soft_cap.is_none() || ctx.accounts.user_deposit.is_some(),
+        CurveLaunchpadError::MissingUserDeposit,
+    );
+
+    //deposits are only tracked for refunds, curves without a soft cap never open any
+    require!(
+        ctx.accounts.bonding_curve.soft_cap.is_some() || ctx.accounts.user_deposit.is_none(),
+        CurveLaunchpadError::UnexpectedUserDeposit,
+    );
+
     //confirm user has enough tokens
     require!(
         ctx.accounts.user_token_account.amount >= token_amount,
         CurveLaunchpadError::InsufficientTokens,
     );
 
+    let (fee_recipient, base_fee_basis_points) = fee_config(
+        &ctx.accounts.global,
+        &ctx.accounts.bonding_curve,
+        &ctx.accounts.launch_config,
+    )?;
+
     //invalid fee recipient
     require!(
-        ctx.accounts.fee_recipient.key == &ctx.accounts.global.fee_recipient,
+        ctx.accounts.fee_recipient.key == &fee_recipient,
         CurveLaunchpadError::InvalidFeeRecipient,
     );
 
+    //the referrer comes with its stats and can't be the user
+    require!(
+        ctx.accounts.referrer.is_some() == ctx.accounts.referrer_stats.is_some()
+            && ctx.accounts.referrer.as_ref().map_or(true, |referrer| referrer.key != ctx.accounts.user.key),
+        CurveLaunchpadError::InvalidReferrer,
+    );
+
     //confirm bonding curve has enough tokens
     require!(
         ctx.accounts.bonding_curve_token_account.amount >= token_amount,
@@ -91,16 +192,26 @@ pub fn sell(ctx: Context<Sell>, token_amount: u64, min_sol_output: u64) -> Resul
 
     require!(token_amount > 0, CurveLaunchpadError::MinSell,);
 
-    let mut amm = amm::amm::AMM::new(
-        ctx.accounts.bonding_curve.virtual_sol_reserves as u128,
-        ctx.accounts.bonding_curve.virtual_token_reserves as u128,
-        ctx.accounts.bonding_curve.real_sol_reserves as u128,
-        ctx.accounts.bonding_curve.real_token_reserves as u128,
-        ctx.accounts.global.initial_virtual_token_reserves as u128,
-    );
+    let mut curve = ctx.accounts.bonding_curve.curve();
 
-    let sell_result = amm.apply_sell(token_amount as u128).unwrap();
-    let fee = calculate_fee(sell_result.sol_amount, ctx.accounts.global.fee_basis_points);
+    //the curve only receives the tokens net of the Token-2022 transfer fee
+    let net_token_amount = token_amount - transfer_fee(&ctx.accounts.mint.to_account_info(), token_amount)?;
+    require!(net_token_amount > 0, CurveLaunchpadError::MinSell);
+
+    //the curve can't buy back more tokens than it sold, vested tokens never came from it
+    let sell_result = curve
+        .apply_sell(net_token_amount as u128)
+        .ok_or(CurveLaunchpadError::InsufficientSOL)?;
+    let fee_basis_points = ctx
+        .accounts
+        .bonding_curve
+        .fee_basis_points(base_fee_basis_points, &Clock::get()?);
+    let TradeFees {
+        fee,
+        protocol_fee,
+        creator_fee,
+        referral_fee,
+    } = trade_fees(sell_result.sol_amount, fee_basis_points, &ctx.accounts.global, ctx.accounts.referrer.is_some());
 
     //the fee is subtracted from the sol amount to confirm the user minimum sol output is met
     let sell_amount_minus_fee = sell_result.sol_amount - fee;
@@ -112,45 +223,112 @@ pub fn sell(ctx: Context<Sell>, token_amount: u64, min_sol_output: u64) -> Resul
     );
 
     //transfer SPL
-    let cpi_accounts = TransferChecked {
-        from: ctx.accounts.user_token_account.to_account_info().clone(),
-        to: ctx
-            .accounts
-            .bonding_curve_token_account
-            .to_account_info()
-            .clone(),
-        authority: ctx.accounts.user.to_account_info().clone(),
-        mint: ctx.accounts.mint.to_account_info().clone(),
-    };
-
-    token::transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-            &[],
-        ),
-        sell_result.token_amount,
-        crate::DEFAULT_DECIMALS.try_into().unwrap()
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.bonding_curve_token_account.to_account_info(),
+        ctx.accounts.user.to_account_info(),
+        ctx.remaining_accounts,
+        token_amount,
+        crate::DEFAULT_DECIMALS.try_into().unwrap(),
+        &[],
     )?;
 
-    //transfer SOL back to user
-    //TODO: check if this is correct
-    let from_account = &ctx.accounts.bonding_curve;
-    let to_account = &ctx.accounts.user;
+    if ctx.accounts.bonding_curve.is_native_quote() {
+        //transfer SOL back to user, the fee legs below are paid out of the same sol amount
+        let from_account = &ctx.accounts.bonding_curve;
+        let to_account = &ctx.accounts.user;
+
+        **from_account.to_account_info().try_borrow_mut_lamports()? -= sell_amount_minus_fee;
+        **to_account.try_borrow_mut_lamports()? += sell_amount_minus_fee;
+
+        //transfer fee to fee recipient
+        **from_account.to_account_info().try_borrow_mut_lamports()? -= protocol_fee;
+        **ctx.accounts.fee_recipient.try_borrow_mut_lamports()? += protocol_fee;
+
+        //transfer referral fee to referrer stats, claimed by the referrer later
+        if let Some(referrer_stats) = ctx.accounts.referrer_stats.as_ref() {
+            **from_account.to_account_info().try_borrow_mut_lamports()? -= referral_fee;
+            **referrer_stats.to_account_info().try_borrow_mut_lamports()? += referral_fee;
+        }
 
-    **from_account.to_account_info().try_borrow_mut_lamports()? -= sell_result.sol_amount;
-    **to_account.try_borrow_mut_lamports()? += sell_result.sol_amount;
+        //transfer creator fee to creator vault
+        **from_account.to_account_info().try_borrow_mut_lamports()? -= creator_fee;
+        **ctx.accounts.creator_vault.to_account_info().try_borrow_mut_lamports()? += creator_fee;
+    } else {
+        let (quote_mint, quote_vault, quote_token_program) = quote_accounts(
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.quote_token_program,
+        )?;
 
-    //transfer fee to fee recipient
-    **from_account.to_account_info().try_borrow_mut_lamports()? -= fee;
-    **ctx.accounts.fee_recipient.try_borrow_mut_lamports()? += fee;
+        //the user gets the sol amount net of the fee, the fee legs are paid out of the rest
+        let mut payees = vec![
+            (
+                quote_account(&ctx.accounts.user_quote_account, &quote_mint.key(), ctx.accounts.user.key)?.to_account_info(),
+                sell_amount_minus_fee,
+            ),
+            (
+                quote_account(&ctx.accounts.fee_recipient_quote_account, &quote_mint.key(), &fee_recipient)?.to_account_info(),
+                protocol_fee,
+            ),
+            (
+                quote_account(&ctx.accounts.creator_quote_account, &quote_mint.key(), &ctx.accounts.bonding_curve.creator)?.to_account_info(),
+                creator_fee,
+            ),
+        ];
+        if let Some(referrer) = ctx.accounts.referrer.as_ref() {
+            payees.push((
+                quote_account(&ctx.accounts.referrer_quote_account, &quote_mint.key(), referrer.key)?.to_account_info(),
+                referral_fee,
+            ));
+        }
 
+        let signer: [&[&[u8]]; 1] = [&[
+            BondingCurve::SEED_PREFIX,
+            ctx.accounts.bonding_curve.mint.as_ref(),
+            &[ctx.bumps.bonding_curve],
+        ]];
+
+        for (to, amount) in payees {
+            transfer_quote(
+                quote_token_program.to_account_info(),
+                quote_vault.to_account_info(),
+                quote_mint,
+                to,
+                ctx.accounts.bonding_curve.to_account_info(),
+                amount,
+                &signer,
+            )?;
+        }
+    }
+
+    //record the referred trade, the totals are lamports so trades in a quote mint aren't counted
+    let is_native_quote = ctx.accounts.bonding_curve.is_native_quote();
+    if let Some(referrer_stats) = ctx.accounts.referrer_stats.as_mut() {
+        referrer_stats.referrer = ctx.accounts.referrer.as_ref().unwrap().key();
+        if is_native_quote {
+            referrer_stats.total_volume = referrer_stats.total_volume.saturating_add(sell_result.sol_amount);
+            referrer_stats.total_earned = referrer_stats.total_earned.saturating_add(referral_fee);
+        }
+    }
+
+    ctx.accounts.creator_vault.bonding_curve = ctx.accounts.bonding_curve.key();
+
+    if let Some(user_deposit) = ctx.accounts.user_deposit.as_mut() {
+        user_deposit.user = ctx.accounts.user.key();
+        user_deposit.mint = ctx.accounts.mint.key();
+        let deposit_sol_amount = user_deposit.record_sell(token_amount);
+        ctx.accounts.bonding_curve.deposited_sol_amount = ctx
+            .accounts
+            .bonding_curve
+            .deposited_sol_amount
+            .saturating_sub(deposit_sol_amount);
+    }
 
     let bonding_curve = &mut ctx.accounts.bonding_curve;
-    bonding_curve.real_token_reserves = amm.real_token_reserves as u64;
-    bonding_curve.real_sol_reserves = amm.real_sol_reserves as u64;
-    bonding_curve.virtual_token_reserves = amm.virtual_token_reserves as u64;
-    bonding_curve.virtual_sol_reserves = amm.virtual_sol_reserves as u64;
+    bonding_curve.set_reserves(curve.reserves());
 
     emit_cpi!(TradeEvent {
         mint: *ctx.accounts.mint.to_account_info().key,
@@ -163,7 +341,44 @@ pub fn sell(ctx: Context<Sell>, token_amount: u64, min_sol_output: u64) -> Resul
         virtual_token_reserves: bonding_curve.virtual_token_reserves,
         real_sol_reserves: bonding_curve.real_sol_reserves,
         real_token_reserves: bonding_curve.real_token_reserves,
+        fee: protocol_fee,
+        fee_basis_points,
+        creator_fee,
+        referrer: ctx.accounts.referrer.as_ref().map(|referrer| referrer.key()),
+        referral_fee,
     });
 
     Ok(())
 }
+
+pub fn sell_exact_out<'info>(ctx: Context<'_, '_, '_, 'info, Sell<'info>>, sol_amount: u64, max_token_input: u64) -> Result<()> {
+    //check if bonding curve is complete
+    require!(
+        !ctx.accounts.bonding_curve.complete,
+        CurveLaunchpadError::BondingCurveComplete,
+    );
+
+    let (_, base_fee_basis_points) = fee_config(
+        &ctx.accounts.global,
+        &ctx.accounts.bonding_curve,
+        &ctx.accounts.launch_config,
+    )?;
+    let curve = ctx.accounts.bonding_curve.curve();
+
+    let token_amount = curve
+        .get_tokens_for_sell(sol_amount as u128, ctx
+            .accounts
+            .bonding_curve
+            .fee_basis_points(base_fee_basis_points, &Clock::get()?) as u128)
+        .ok_or(CurveLaunchpadError::InsufficientSOL)?;
+    let token_amount = amount_with_transfer_fee(&ctx.accounts.mint.to_account_info(), token_amount as u64)?;
+
+    //confirm the user does not spend more than max_token_input, including the transfer fee
+    require!(
+        token_amount <= max_token_input,
+        CurveLaunchpadError::MaxTokenInputExceeded,
+    );
+
+    //sol_amount is the min output, so the user receives at least the requested amount net of fee
+    sell(ctx, token_amount, sol_amount)
+}
@@ -0,0 +1,53 @@
+use crate::{
+    state::{BondingCurve, Global},
+    CurveLaunchpadError, SetFrozenEvent,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetFrozen<'info> {
+    #[account(
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    user: Signer<'info>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+}
+
+pub fn set_frozen(ctx: Context<SetFrozen>, frozen: bool) -> Result<()> {
+    //confirm program is initialized
+    require!(
+        ctx.accounts.global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //confirm user is the guardian
+    require!(
+        ctx.accounts.global.guardian == *ctx.accounts.user.to_account_info().key,
+        CurveLaunchpadError::InvalidGuardian
+    );
+
+    ctx.accounts.bonding_curve.frozen = frozen;
+
+    emit_cpi!(SetFrozenEvent {
+        guardian: ctx.accounts.global.guardian,
+        mint: *ctx.accounts.mint.to_account_info().key,
+        bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
+        frozen,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
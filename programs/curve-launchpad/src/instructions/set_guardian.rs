@@ -0,0 +1,40 @@
+use crate::{state::Global, CurveLaunchpadError, SetGuardianEvent};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    user: Signer<'info>,
+}
+
+pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    let global = &mut ctx.accounts.global;
+
+    //confirm program is initialized
+    require!(
+        global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //confirm user is the authority
+    require!(
+        global.authority == *ctx.accounts.user.to_account_info().key,
+        CurveLaunchpadError::InvalidAuthority
+    );
+
+    global.guardian = guardian;
+
+    emit_cpi!(SetGuardianEvent {
+        authority: global.authority,
+        guardian,
+    });
+
+    Ok(())
+}
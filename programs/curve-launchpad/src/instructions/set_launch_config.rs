@@ -0,0 +1,92 @@
+use crate::{
+    state::{Global, GraduationTarget, LaunchConfig, LaunchFeeSchedule, LaunchParams},
+    CurveLaunchpadError, SetLaunchConfigEvent,
+};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct SetLaunchConfig<'info> {
+    #[account(
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + LaunchConfig::INIT_SPACE,
+        seeds = [LaunchConfig::SEED_PREFIX, index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    launch_config: Box<Account<'info, LaunchConfig>>,
+
+    #[account(mut)]
+    user: Signer<'info>,
+
+    system_program: Program<'info, System>,
+}
+
+pub fn set_launch_config(
+    ctx: Context<SetLaunchConfig>,
+    index: u16,
+    fee_recipient: Pubkey,
+    fee_basis_points: u64,
+    launch_params: LaunchParams,
+    launch_fee_schedule: LaunchFeeSchedule,
+    quote_mint: Pubkey,
+    graduation_target: GraduationTarget,
+) -> Result<()> {
+    //confirm program is initialized
+    require!(
+        ctx.accounts.global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //confirm user is the authority
+    require!(
+        ctx.accounts.global.authority == *ctx.accounts.user.to_account_info().key,
+        CurveLaunchpadError::InvalidAuthority
+    );
+
+    require!(
+        fee_basis_points <= 10000,
+        CurveLaunchpadError::InvalidLaunchConfig
+    );
+
+    require!(
+        launch_params.is_valid(),
+        CurveLaunchpadError::InvalidLaunchParams
+    );
+
+    //launch configs are held to the same cap as creators
+    require!(
+        launch_fee_schedule.is_valid()
+            && launch_fee_schedule.is_within(ctx.accounts.global.max_launch_fee_basis_points),
+        CurveLaunchpadError::InvalidLaunchFeeSchedule
+    );
+
+    let launch_config = &mut ctx.accounts.launch_config;
+    launch_config.index = index;
+    launch_config.fee_recipient = fee_recipient;
+    launch_config.fee_basis_points = fee_basis_points;
+    launch_config.launch_params = launch_params;
+    launch_config.launch_fee_schedule = launch_fee_schedule;
+    launch_config.quote_mint = quote_mint;
+    launch_config.graduation_target = graduation_target;
+
+    emit_cpi!(SetLaunchConfigEvent {
+        launch_config: ctx.accounts.launch_config.key(),
+        index,
+        fee_recipient,
+        fee_basis_points,
+        launch_params,
+        launch_fee_schedule,
+        quote_mint,
+        graduation_target,
+    });
+
+    Ok(())
+}
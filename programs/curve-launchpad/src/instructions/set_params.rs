@@ -1,4 +1,4 @@
-use crate::{state::Global, CurveLaunchpadError, SetParamsEvent};
+use crate::{state::{Global, GraduationTarget, LaunchFeeSchedule, LaunchParamsLimits, TransferFeeParams}, CurveLaunchpadError, SetParamsEvent};
 use anchor_lang::prelude::*;
 
 #[event_cpi]
@@ -18,11 +18,20 @@ pub struct SetParams<'info> {
 
 pub fn set_params(
     ctx: Context<SetParams>,
+    fee_recipient: Pubkey,
+    withdraw_authority: Pubkey,
     initial_virtual_token_reserves: u64,
     initial_virtual_sol_reserves: u64,
     initial_real_token_reserves: u64,
     initial_token_supply: u64,
     fee_basis_points: u64,
+    creator_fee_share_basis_points: u64,
+    referral_fee_share_basis_points: u64,
+    launch_fee_schedule: LaunchFeeSchedule,
+    graduation_target: GraduationTarget,
+    launch_params_limits: LaunchParamsLimits,
+    max_transfer_fee: TransferFeeParams,
+    max_launch_fee_basis_points: u64,
 ) -> Result<()> {
     let global = &mut ctx.accounts.global;
 
@@ -37,23 +46,68 @@ pub fn set_params(
         global.authority == *ctx.accounts.user.to_account_info().key,
         CurveLaunchpadError::InvalidAuthority
     ); 
+
+    require!(
+        creator_fee_share_basis_points <= 10000,
+        CurveLaunchpadError::InvalidCreatorFeeShare
+    );
+
+    //the creator and referrer shares come out of the same fee
+    require!(
+        creator_fee_share_basis_points + referral_fee_share_basis_points <= 10000,
+        CurveLaunchpadError::InvalidReferralFeeShare
+    );
+
+    require!(
+        max_launch_fee_basis_points <= 10000,
+        CurveLaunchpadError::InvalidLaunchFeeSchedule
+    );
+
+    require!(
+        launch_fee_schedule.is_valid() && launch_fee_schedule.is_within(max_launch_fee_basis_points),
+        CurveLaunchpadError::InvalidLaunchFeeSchedule
+    );
+
+    require!(
+        launch_params_limits.is_valid(),
+        CurveLaunchpadError::InvalidLaunchParams
+    );
+
+    require!(
+        max_transfer_fee.is_valid(),
+        CurveLaunchpadError::InvalidTransferFee
+    );
     
-    global.fee_recipient = *ctx.accounts.user.to_account_info().key;
+    global.fee_recipient = fee_recipient;
     global.initial_virtual_token_reserves = initial_virtual_token_reserves;
     global.initial_virtual_sol_reserves = initial_virtual_sol_reserves;
     global.initial_real_token_reserves = initial_real_token_reserves;
     global.initial_token_supply = initial_token_supply;
     global.fee_basis_points = fee_basis_points;
-    global.withdraw_authority = *ctx.accounts.user.to_account_info().key;
+    global.creator_fee_share_basis_points = creator_fee_share_basis_points;
+    global.referral_fee_share_basis_points = referral_fee_share_basis_points;
+    global.withdraw_authority = withdraw_authority;
+    global.launch_fee_schedule = launch_fee_schedule;
+    global.graduation_target = graduation_target;
+    global.launch_params_limits = launch_params_limits;
+    global.max_transfer_fee = max_transfer_fee;
+    global.max_launch_fee_basis_points = max_launch_fee_basis_points;
 
     emit_cpi!(SetParamsEvent {
-        fee_recipient: *ctx.accounts.user.to_account_info().key,
-        withdraw_authority: *ctx.accounts.user.to_account_info().key,
+        fee_recipient,
+        withdraw_authority,
         initial_virtual_token_reserves,
         initial_virtual_sol_reserves,
         initial_real_token_reserves,
         initial_token_supply,
         fee_basis_points,
+        creator_fee_share_basis_points,
+        referral_fee_share_basis_points,
+        launch_fee_schedule,
+        graduation_target,
+        launch_params_limits,
+        max_transfer_fee,
+        max_launch_fee_basis_points,
     });
 
     Ok(())
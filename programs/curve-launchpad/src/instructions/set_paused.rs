@@ -0,0 +1,41 @@
+use crate::{state::Global, CurveLaunchpadError, SetPausedEvent};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    user: Signer<'info>,
+}
+
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let global = &mut ctx.accounts.global;
+
+    //confirm program is initialized
+    require!(
+        global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //confirm user is the guardian
+    require!(
+        global.guardian == *ctx.accounts.user.to_account_info().key,
+        CurveLaunchpadError::InvalidGuardian
+    );
+
+    global.paused = paused;
+
+    emit_cpi!(SetPausedEvent {
+        guardian: global.guardian,
+        paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
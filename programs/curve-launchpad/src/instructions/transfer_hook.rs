@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_token_2022::extension::{transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions};
+
+use crate::{check_transfer_limit, state::{BondingCurve, CreatorVault, UserTransferData, Vesting}, CurveLaunchpadError};
+
+// Order of accounts matters for this struct.
+// The first 4 accounts are the accounts required for token transfer (source, mint, destination, owner)
+// Remaining accounts are the extra accounts required from the ExtraAccountMetaList account
+// These accounts are provided via CPI to this program from the token2022 program
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(
+        token::mint = mint,
+    )]
+    pub source_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        token::mint = mint,
+    )]
+    pub destination_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: source token account owner or delegate, can be SystemAccount or PDA owned by another program
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: ExtraAccountMetaList Account,
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    /// CHECK: transfer data of the source token account owner, only read when the owner is rate limited.
+    /// Wallets that never traded against the curve create one with `init_user_transfer_data`
+    #[account(
+        mut,
+        seeds = [b"user", source_token.owner.as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub user_transfer_data: UncheckedAccount<'info>,
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+}
+
+pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+    //only token2022 runs the hook, in the middle of a transfer out of the source account
+    {
+        let source_token = ctx.accounts.source_token.to_account_info();
+        let data = source_token.try_borrow_data()?;
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        require!(
+            bool::from(account.get_extension::<TransferHookAccount>()?.transferring),
+            CurveLaunchpadError::NotTransferring,
+        );
+    }
+
+    let bonding_curve = &ctx.accounts.bonding_curve;
+
+    //trading moved off the curve, transfers are no longer limited
+    if bonding_curve.complete {
+        return Ok(());
+    }
+
+    //trades against the curve are limited in buy and sell
+    if ctx.accounts.source_token.key() == bonding_curve.token_account
+        || ctx.accounts.destination_token.key() == bonding_curve.token_account
+    {
+        return Ok(());
+    }
+
+    let is_creator = ctx.accounts.source_token.owner == bonding_curve.creator;
+    if bonding_curve.rate_limit_policy.limit_basis_points(is_creator) == 0 {
+        return Ok(());
+    }
+
+    //vesting releases are limited by the schedule
+    let (vesting, _) = Pubkey::find_program_address(
+        &[Vesting::SEED_PREFIX, ctx.accounts.mint.key().as_ref()],
+        &crate::ID,
+    );
+    if ctx.accounts.source_token.owner == vesting {
+        return Ok(());
+    }
+
+    //withheld transfer fees claimed out of the creator vault
+    let (creator_vault, _) = Pubkey::find_program_address(
+        &[CreatorVault::SEED_PREFIX, bonding_curve.key().as_ref()],
+        &crate::ID,
+    );
+    if ctx.accounts.source_token.owner == creator_vault {
+        return Ok(());
+    }
+
+    //the bucket has to be saved between transfers, so wallets without transfer data can't transfer
+    require!(
+        !ctx.accounts.user_transfer_data.data_is_empty(),
+        CurveLaunchpadError::UserTransferDataNotInitialized,
+    );
+
+    require_keys_eq!(
+        *ctx.accounts.user_transfer_data.owner,
+        crate::ID,
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+
+    let mut data = ctx.accounts.user_transfer_data.try_borrow_mut_data()?;
+    let mut user_transfer_data = UserTransferData::try_deserialize(&mut &data[..])?;
+
+    check_transfer_limit(
+        &bonding_curve.rate_limit_policy,
+        is_creator,
+        &mut user_transfer_data,
+        Clock::get()?.unix_timestamp,
+        bonding_curve.token_total_supply,
+        amount,
+    )?;
+
+    user_transfer_data.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{spl_token_metadata_interface::state::Field, Mint, TokenInterface};
+
+use crate::{
+    state::BondingCurve,
+    update_token_metadata, CurveLaunchpadError, MetadataField, UpdateMetadataEvent,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    #[account(mut)]
+    creator: Signer<'info>,
+
+    #[account(mut)]
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Using seed to validate mint_authority account, the update authority of the metadata
+    #[account(
+        seeds=[b"mint-authority"],
+        bump,
+    )]
+    mint_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+        has_one = creator @ CurveLaunchpadError::InvalidCreator,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    system_program: Program<'info, System>,
+
+    token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn update_metadata(
+    ctx: Context<UpdateMetadata>,
+    name: Option<String>,
+    symbol: Option<String>,
+    uri: Option<String>,
+    additional_metadata: Vec<MetadataField>,
+) -> Result<()> {
+    //the metadata is frozen once the curve graduates
+    require!(
+        !ctx.accounts.bonding_curve.complete,
+        CurveLaunchpadError::BondingCurveComplete,
+    );
+
+    let fields: Vec<(Field, String)> = [
+        name.clone().map(|name| (Field::Name, name)),
+        symbol.clone().map(|symbol| (Field::Symbol, symbol)),
+        uri.clone().map(|uri| (Field::Uri, uri)),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(
+        additional_metadata
+            .iter()
+            .map(|metadata_field| (Field::Key(metadata_field.key.clone()), metadata_field.value.clone())),
+    )
+    .collect();
+
+    let seeds = &["mint-authority".as_bytes(), &[ctx.bumps.mint_authority]];
+    let signer = [&seeds[..]];
+
+    update_token_metadata(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.mint_authority.to_account_info(),
+        ctx.accounts.creator.to_account_info(),
+        fields,
+        &signer,
+    )?;
+
+    emit_cpi!(UpdateMetadataEvent {
+        mint: *ctx.accounts.mint.to_account_info().key,
+        creator: *ctx.accounts.creator.to_account_info().key,
+        name,
+        symbol,
+        uri,
+        additional_metadata,
+    });
+
+    Ok(())
+}
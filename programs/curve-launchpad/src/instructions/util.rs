@@ -1,6 +1,20 @@
-use anchor_lang::{accounts::account::Account, solana_program::{account_info::AccountInfo, clock::Clock, program_error::ProgramError, sysvar::Sysvar}};
+use anchor_lang::{accounts::account::Account, prelude::*, solana_program::{account_info::AccountInfo, keccak::hashv}, system_program};
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+        onchain::invoke_transfer_checked,
+    },
+    token_2022_extensions::{token_metadata_update_field, TokenMetadataUpdateField},
+    token_interface::{
+        self,
+        spl_token_metadata_interface::state::{Field, TokenMetadata},
+        Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
 
-use crate::{BondingCurve, state::UserTransferData};
+use crate::{amm::{isqrt, mul_div_floor}, BondingCurve, state::{Global, GraduationTarget, LaunchConfig, LaunchFeeDecay, LaunchFeeSchedule, RateLimitPolicy, UserTransferData}, CurveLaunchpadError, LAUNCH_FEE_HALVINGS,
+    MAX_ADDITIONAL_METADATA_FIELDS, MAX_METADATA_KEY_LENGTH, MAX_METADATA_VALUE_LENGTH, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH};
 
 
 pub fn calculate_fee(
@@ -9,39 +23,430 @@ pub fn calculate_fee(
 ) -> u64 {
     amount * fee_basis_points / 10000
 }
-pub fn check_buy_sell<'a>( user_transfer_data: &mut Account<'a, UserTransferData>,user_account: AccountInfo<'a>,  system_account: AccountInfo<'a>, bonding_curve: Account<'a, BondingCurve>, amount: u64) -> Result<(), ProgramError>
-{
-    
-    // Anti-bot measures
-    let clock = Clock::get()?;
-    let current_timestamp = clock.unix_timestamp;
-
-    // Get or initialize the user's transfer data
-
-    // Check if the user is a dev
-    let is_dev = bonding_curve.creator == *user_account.key;
-
-    // Calculate the maximum allowed transfer amount
-    let max_transfer_percentage = if is_dev {
-        let seconds_since_last_transfer = (current_timestamp - user_transfer_data.last_transfer_timestamp) as f64;
-        // This is in seconds
-        let max_percentage = (seconds_since_last_transfer / 3600.0) * 0.005;
-        max_percentage.min(0.005) // Cap at 0.5% maximum
+
+/// Trading fee on a trade of `sol_amount`, split between the protocol, the creator and the referrer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeFees {
+    pub fee: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub referral_fee: u64,
+}
+
+pub fn trade_fees(sol_amount: u64, fee_basis_points: u64, global: &Global, has_referrer: bool) -> TradeFees {
+    split_fee(
+        calculate_fee(sol_amount, fee_basis_points),
+        global.creator_fee_share_basis_points,
+        if has_referrer { global.referral_fee_share_basis_points } else { 0 },
+    )
+}
+
+/// Fee recipient and base trading fee of a curve, from its launch config or else `Global`.
+pub fn fee_config(global: &Global, bonding_curve: &BondingCurve, launch_config: &Option<Box<Account<'_, LaunchConfig>>>) -> Result<(Pubkey, u64)> {
+    if bonding_curve.launch_config == Pubkey::default() {
+        return Ok((global.fee_recipient, global.fee_basis_points));
+    }
+
+    let launch_config = launch_config.as_ref().ok_or(CurveLaunchpadError::InvalidLaunchConfig)?;
+    Ok((launch_config.fee_recipient, launch_config.fee_basis_points))
+}
+
+/// Graduation target of a curve, from its launch config or else `Global`.
+pub fn graduation_target(global: &Global, bonding_curve: &BondingCurve, launch_config: &Option<Box<Account<'_, LaunchConfig>>>) -> Result<GraduationTarget> {
+    if bonding_curve.launch_config == Pubkey::default() {
+        return Ok(global.graduation_target);
+    }
+
+    let launch_config = launch_config.as_ref().ok_or(CurveLaunchpadError::InvalidLaunchConfig)?;
+    Ok(launch_config.graduation_target)
+}
+
+/// Fee `elapsed` seconds or slots after launch, decaying from the schedule's initial fee to `base_fee_basis_points`.
+pub fn launch_fee_basis_points(base_fee_basis_points: u64, schedule: &LaunchFeeSchedule, elapsed: u64) -> u64 {
+    if elapsed >= schedule.duration || schedule.initial_fee_basis_points <= base_fee_basis_points {
+        return base_fee_basis_points;
+    }
+
+    let excess = schedule.initial_fee_basis_points - base_fee_basis_points;
+    let decayed = match schedule.decay {
+        LaunchFeeDecay::Linear => {
+            (excess as u128 * (schedule.duration - elapsed) as u128 / schedule.duration as u128) as u64
+        }
+        LaunchFeeDecay::Exponential => {
+            let halvings = elapsed as u128 * LAUNCH_FEE_HALVINGS as u128 / schedule.duration as u128;
+            excess >> halvings
+        }
+    };
+
+    base_fee_basis_points + decayed
+}
+
+pub fn split_fee(fee: u64, creator_fee_share_basis_points: u64, referral_fee_share_basis_points: u64) -> TradeFees {
+    let creator_fee = calculate_fee(fee, creator_fee_share_basis_points);
+    let referral_fee = calculate_fee(fee, referral_fee_share_basis_points);
+
+    TradeFees {
+        fee,
+        protocol_fee: fee - creator_fee - referral_fee,
+        creator_fee,
+        referral_fee,
+    }
+}
+/// How far the spot price moved, in basis points of the price before the trade.
+pub fn price_impact_bps(price_before: u128, price_after: u128) -> u64 {
+    mul_div_floor(price_before.abs_diff(price_after), 10000, price_before)
+        .map_or(u64::MAX, |impact| impact.min(u64::MAX as u128) as u64)
+}
+
+/// Whirlpool sqrt price (Q64.64) for a price of `numerator / denominator` token b per token a.
+pub fn sqrt_price_x64(numerator: u128, denominator: u128) -> Option<u128> {
+    let price_x64 = mul_div_floor(numerator, 1 << 64, denominator)?;
+
+    if price_x64 >> 64 == 0 {
+        Some(isqrt(price_x64 << 64))
     } else {
-        1.0 // 100% for regular users
+        isqrt(price_x64).checked_mul(1 << 32)
+    }
+}
+
+/// Largest liquidity that can be deposited between `sqrt_price_lower` and `sqrt_price_upper`
+/// without needing more than `amount_a` or `amount_b`, when the pool price is inside the range.
+pub fn liquidity_for_amounts(
+    sqrt_price: u128,
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    amount_a: u64,
+    amount_b: u64,
+) -> Option<u128> {
+    if sqrt_price <= sqrt_price_lower || sqrt_price >= sqrt_price_upper {
+        return None;
+    }
+
+    let price_factor = mul_div_floor(sqrt_price, sqrt_price_upper, sqrt_price_upper - sqrt_price)?;
+    let liquidity_a = mul_div_floor(amount_a as u128, price_factor, 1 << 64)?;
+    let liquidity_b = mul_div_floor(amount_b as u128, 1 << 64, sqrt_price - sqrt_price_lower)?;
+
+    Some(liquidity_a.min(liquidity_b))
+}
+
+pub fn check_buy_sell(user_transfer_data: &mut UserTransferData, user_account: AccountInfo, bonding_curve: &BondingCurve, amount: u64) -> Result<()>
+{
+    check_transfer_limit(
+        &bonding_curve.rate_limit_policy,
+        bonding_curve.creator == *user_account.key,
+        user_transfer_data,
+        Clock::get()?.unix_timestamp,
+        bonding_curve.token_total_supply,
+        amount,
+    )
+}
+
+/// Tokens available `elapsed` seconds after the last transfer left `available_amount`,
+/// refilling linearly up to `cap` over `window_seconds`.
+pub fn transfer_allowance(cap: u64, available_amount: u64, elapsed: i64, window_seconds: i64) -> u64 {
+    if window_seconds <= 0 {
+        return cap;
+    }
+    let elapsed = elapsed.clamp(0, window_seconds) as u128;
+    let refill = (cap as u128 * elapsed / window_seconds as u128) as u64;
+    available_amount.saturating_add(refill).min(cap)
+}
+
+pub fn check_transfer_limit(
+    policy: &RateLimitPolicy,
+    is_creator: bool,
+    user_transfer_data: &mut UserTransferData,
+    timestamp: i64,
+    total_supply: u64,
+    amount: u64,
+) -> Result<()> {
+    let limit_basis_points = policy.limit_basis_points(is_creator);
+
+    if limit_basis_points > 0 {
+        let cap = (total_supply as u128 * limit_basis_points as u128 / 10000) as u64;
+        let allowance = transfer_allowance(
+            cap,
+            user_transfer_data.available_amount,
+            timestamp.saturating_sub(user_transfer_data.last_transfer_timestamp),
+            policy.window_seconds,
+        );
+
+        require!(allowance > 0, CurveLaunchpadError::TransferCooldownNotMet);
+        require!(amount <= allowance, CurveLaunchpadError::TransferLimitExceeded);
+
+        user_transfer_data.available_amount = allowance - amount;
+    }
+
+    user_transfer_data.last_transfer_timestamp = timestamp;
+
+    Ok(())
+}
+
+/// Fails when a buy would leave a wallet with more than `max_wallet_basis_points` of the supply.
+pub fn check_max_wallet(total_supply: u64, max_wallet_basis_points: u64, wallet_amount: u64) -> Result<()> {
+    if max_wallet_basis_points == 0 {
+        return Ok(());
+    }
+
+    let max_wallet_amount = (total_supply as u128 * max_wallet_basis_points as u128 / 10000) as u64;
+    require!(
+        wallet_amount <= max_wallet_amount,
+        CurveLaunchpadError::MaxWalletExceeded
+    );
+
+    Ok(())
+}
+
+/// Share of `sol_amount` paid for `token_amount_held` that `token_amount` of them account for.
+pub fn average_cost(sol_amount: u64, token_amount_held: u64, token_amount: u64) -> u64 {
+    if token_amount_held == 0 {
+        return 0;
+    }
+    (sol_amount as u128 * token_amount.min(token_amount_held) as u128 / token_amount_held as u128) as u64
+}
+
+/// Leaf of the presale allowlist for `wallet`, allowed to buy up to `max_token_amount`.
+pub fn presale_leaf(wallet: &Pubkey, max_token_amount: u64) -> [u8; 32] {
+    hashv(&[wallet.as_ref(), &max_token_amount.to_le_bytes()]).to_bytes()
+}
+
+/// Refund of a deposit of `deposit_sol_amount`, scaled down pro-rata when the
+/// curve holds less than the `deposited_sol_amount` owed to all depositors.
+pub fn refund_amount(deposit_sol_amount: u64, real_sol_reserves: u64, deposited_sol_amount: u64) -> Option<u64> {
+    if deposited_sol_amount <= real_sol_reserves {
+        return Some(deposit_sol_amount);
+    }
+
+    mul_div_floor(deposit_sol_amount as u128, real_sol_reserves as u128, deposited_sol_amount as u128)
+        .map(|refund| refund as u64)
+}
+
+/// Verifies a Merkle proof where each pair of nodes is hashed in sorted order.
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+
+    computed == *root
+}
+
+/// Tokens of a vesting allocation released `elapsed` seconds after the start,
+/// nothing before the cliff and linearly up to `total_amount` over `duration_seconds`.
+pub fn vested_amount(total_amount: u64, elapsed: i64, cliff_seconds: i64, duration_seconds: i64) -> u64 {
+    if elapsed < cliff_seconds {
+        return 0;
+    }
+    if elapsed >= duration_seconds {
+        return total_amount;
+    }
+    (total_amount as u128 * elapsed as u128 / duration_seconds as u128) as u64
+}
+
+/// Whether `mint` can price a curve, transfer fees and hooks would leave the quote vault short of the reserves.
+pub fn is_supported_quote_mint(mint: &AccountInfo) -> Result<bool> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(!mint.get_extension_types()?.iter().any(|extension_type| {
+        matches!(extension_type, ExtensionType::TransferFeeConfig | ExtensionType::TransferHook)
+    }))
+}
+
+fn transfer_fee_config(mint: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Token-2022 fee withheld from a transfer of `amount` this epoch, 0 for mints without a transfer fee.
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    match transfer_fee_config(mint)? {
+        Some(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(error!(CurveLaunchpadError::InvalidTransferFee)),
+        None => Ok(0),
+    }
+}
+
+/// Amount to transfer for `net_amount` to arrive after the Token-2022 transfer fee.
+pub fn amount_with_transfer_fee(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    let fee = match transfer_fee_config(mint)? {
+        Some(config) => config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+            .ok_or(error!(CurveLaunchpadError::InvalidTransferFee))?,
+        None => 0,
     };
 
-    let total_supply = bonding_curve.token_total_supply;
-    let max_allowed_amount = (total_supply as f64 * max_transfer_percentage) as u64;
+    net_amount
+        .checked_add(fee)
+        .ok_or(error!(CurveLaunchpadError::InvalidTransferFee))
+}
+
+/// `transfer_checked` that also passes the accounts the mint's transfer hook needs.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_hook<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint,
+        to,
+        authority,
+        remaining_accounts,
+        amount,
+        decimals,
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+/// Moves `amount` of an SPL quote mint with `transfer_checked`. Quote mints with a transfer hook aren't supported.
+pub fn transfer_quote<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program,
+            TransferChecked {
+                from,
+                mint: mint.to_account_info(),
+                to,
+                authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
 
-    // Check if the transfer amount exceeds the maximum allowed
-    if amount > max_allowed_amount {
-        return Err(ProgramError::InvalidInstructionData.into());
+/// Quote mint, vault and token program of a curve priced in an SPL mint, all required.
+#[allow(clippy::type_complexity)]
+pub fn quote_accounts<'a, 'info>(
+    quote_mint: &'a Option<Box<InterfaceAccount<'info, Mint>>>,
+    quote_vault: &'a Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    quote_token_program: &'a Option<Interface<'info, TokenInterface>>,
+) -> Result<(&'a InterfaceAccount<'info, Mint>, &'a InterfaceAccount<'info, TokenAccount>, &'a Interface<'info, TokenInterface>)> {
+    match (quote_mint, quote_vault, quote_token_program) {
+        (Some(quote_mint), Some(quote_vault), Some(quote_token_program)) => Ok((quote_mint, quote_vault, quote_token_program)),
+        _ => err!(CurveLaunchpadError::InvalidQuoteAccount),
     }
+}
+
+/// Token account of `owner` in the quote mint.
+pub fn quote_account<'a, 'info>(
+    account: &'a Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    quote_mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<&'a InterfaceAccount<'info, TokenAccount>> {
+    let account = account.as_deref().ok_or(CurveLaunchpadError::InvalidQuoteAccount)?;
+    require!(
+        account.mint == *quote_mint && account.owner == *owner,
+        CurveLaunchpadError::InvalidQuoteAccount
+    );
+
+    Ok(account)
+}
 
-    // Update user's transfer data
-    user_transfer_data.last_transfer_timestamp = current_timestamp;
- 
+/// Checks the lengths of the token metadata fields written at `create` or `update_metadata`.
+pub fn check_metadata_fields(fields: &[(Field, String)]) -> Result<()> {
+    for (field, value) in fields {
+        match field {
+            Field::Name => require!(value.len() <= MAX_NAME_LENGTH, CurveLaunchpadError::NameTooLong),
+            Field::Symbol => require!(value.len() <= MAX_SYMBOL_LENGTH, CurveLaunchpadError::SymbolTooLong),
+            Field::Uri => require!(value.len() <= MAX_URI_LENGTH, CurveLaunchpadError::UriTooLong),
+            Field::Key(key) => require!(
+                !key.is_empty() && key.len() <= MAX_METADATA_KEY_LENGTH && value.len() <= MAX_METADATA_VALUE_LENGTH,
+                CurveLaunchpadError::InvalidAdditionalMetadata
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes `fields` to the token metadata of `mint`, topping it up from `payer` so it stays
+/// rent exempt as the metadata grows.
+#[allow(clippy::too_many_arguments)]
+pub fn update_token_metadata<'info>(
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    update_authority: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    fields: Vec<(Field, String)>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    check_metadata_fields(&fields)?;
+
+    let mut metadata = {
+        let data = mint.try_borrow_data()?;
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+        mint.get_variable_len_extension::<TokenMetadata>()?
+    };
+    let size_before = metadata.tlv_size_of()?;
+    for (field, value) in &fields {
+        metadata.update(field.clone(), value.clone());
+    }
+
+    require!(
+        metadata.additional_metadata.len() <= MAX_ADDITIONAL_METADATA_FIELDS,
+        CurveLaunchpadError::InvalidAdditionalMetadata
+    );
+
+    let data_len = mint.data_len() + metadata.tlv_size_of()?.saturating_sub(size_before);
+    let lamports = Rent::get()?.minimum_balance(data_len).saturating_sub(mint.lamports());
+    if lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: payer,
+                    to: mint.clone(),
+                },
+            ),
+            lamports,
+        )?;
+    }
+
+    for (field, value) in fields {
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataUpdateField {
+                    token_program_id: token_program.clone(),
+                    metadata: mint.clone(),
+                    update_authority: update_authority.clone(),
+                },
+                signer_seeds,
+            ),
+            field,
+            value,
+        )?;
+    }
 
     Ok(())
 }
@@ -49,6 +454,7 @@ pub fn check_buy_sell<'a>( user_transfer_data: &mut Account<'a, UserTransferData
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::LaunchFeeUnit;
 
     #[test]
     fn test_calculate_fee() {
@@ -60,4 +466,195 @@ mod tests {
         assert_eq!(calculate_fee(1000, 50), 5); //0.5% fee
         assert_eq!(calculate_fee(100, 0), 0); //0% fee
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_launch_fee_basis_points() {
+        let linear = LaunchFeeSchedule {
+            initial_fee_basis_points: 1050,
+            duration: 100,
+            unit: LaunchFeeUnit::Slots,
+            decay: LaunchFeeDecay::Linear,
+        };
+        assert_eq!(launch_fee_basis_points(50, &linear, 0), 1050);
+        assert_eq!(launch_fee_basis_points(50, &linear, 50), 550);
+        assert_eq!(launch_fee_basis_points(50, &linear, 99), 60);
+        assert_eq!(launch_fee_basis_points(50, &linear, 100), 50);
+        assert_eq!(launch_fee_basis_points(50, &linear, u64::MAX), 50);
+        assert_eq!(launch_fee_basis_points(2000, &linear, 0), 2000); //never below the base fee
+        assert_eq!(launch_fee_basis_points(50, &LaunchFeeSchedule::default(), 0), 50); //disabled
+
+        let exponential = LaunchFeeSchedule {
+            decay: LaunchFeeDecay::Exponential,
+            ..linear
+        };
+        assert_eq!(launch_fee_basis_points(50, &exponential, 0), 1050);
+        assert_eq!(launch_fee_basis_points(50, &exponential, 10), 550);
+        assert_eq!(launch_fee_basis_points(50, &exponential, 25), 300);
+        assert_eq!(launch_fee_basis_points(50, &exponential, 99), 51);
+        assert_eq!(launch_fee_basis_points(50, &exponential, 100), 50);
+    }
+
+    #[test]
+    fn test_split_fee() {
+        let fees = split_fee(1000, 2000, 1000); //20% creator, 10% referrer
+        assert_eq!((fees.protocol_fee, fees.creator_fee, fees.referral_fee), (700, 200, 100));
+        assert_eq!(split_fee(9, 3333, 3333).protocol_fee, 5); //shares round down in favour of the protocol
+        assert_eq!(split_fee(1000, 0, 0).protocol_fee, 1000);
+    }
+
+    #[test]
+    fn test_price_impact_bps() {
+        assert_eq!(price_impact_bps(100, 100), 0);
+        assert_eq!(price_impact_bps(100, 101), 100);
+        assert_eq!(price_impact_bps(100, 99), 100);
+        assert_eq!(price_impact_bps(3, 4), 3333);
+        assert_eq!(price_impact_bps(0, 1), u64::MAX);
+    }
+
+    #[test]
+    fn test_transfer_allowance() {
+        let cap = 5_000_000_000_000;
+        assert_eq!(transfer_allowance(cap, 0, 0, 3600), 0);
+        assert_eq!(transfer_allowance(cap, 0, -10, 3600), 0);
+        assert_eq!(transfer_allowance(cap, 0, 1800, 3600), cap / 2); //half the window
+        assert_eq!(transfer_allowance(cap, cap / 4, 1800, 3600), cap * 3 / 4);
+        assert_eq!(transfer_allowance(cap, cap / 2, 3600, 3600), cap); //capped
+        assert_eq!(transfer_allowance(cap, 0, i64::MAX, 3600), cap);
+        assert_eq!(transfer_allowance(u64::MAX, 0, 3600, 3600), u64::MAX);
+    }
+
+    #[test]
+    fn test_check_transfer_limit() {
+        let supply = 1_000_000_000_000_000;
+        let policy = RateLimitPolicy {
+            creator_limit_basis_points: 50,
+            user_limit_basis_points: 0,
+            window_seconds: 3600,
+        };
+        let mut data = UserTransferData::default();
+
+        //users are unlimited
+        assert!(check_transfer_limit(&policy, false, &mut data, 100, supply, supply).is_ok());
+        assert_eq!(data.last_transfer_timestamp, 100);
+
+        //a fresh creator gets the full 0.5%
+        let mut data = UserTransferData::default();
+        assert!(check_transfer_limit(&policy, true, &mut data, 3600, supply, supply / 200 + 1).is_err()); //limit
+        assert!(check_transfer_limit(&policy, true, &mut data, 3600, supply, supply / 400).is_ok());
+        assert_eq!(data.available_amount, supply / 400);
+
+        //the rest of the bucket is still there in the same second
+        assert!(check_transfer_limit(&policy, true, &mut data, 3600, supply, supply / 400).is_ok());
+        assert!(check_transfer_limit(&policy, true, &mut data, 3600, supply, 1).is_err()); //cooldown
+
+        //refills linearly
+        assert!(check_transfer_limit(&policy, true, &mut data, 3636, supply, supply / 20000).is_ok());
+        assert_eq!(data.available_amount, 0);
+    }
+
+    #[test]
+    fn test_check_max_wallet() {
+        let supply = 1_000_000_000_000_000;
+        assert!(check_max_wallet(supply, 0, supply).is_ok()); //no cap
+        assert!(check_max_wallet(supply, 200, supply / 50).is_ok()); //2%
+        assert!(check_max_wallet(supply, 200, supply / 50 + 1).is_err());
+        assert!(check_max_wallet(u64::MAX, 10000, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_average_cost() {
+        assert_eq!(average_cost(1_000, 0, 10), 0);
+        assert_eq!(average_cost(1_000, 100, 25), 250);
+        assert_eq!(average_cost(1_000, 100, 100), 1_000);
+        assert_eq!(average_cost(1_000, 100, 200), 1_000); //can't return more than was paid
+        assert_eq!(average_cost(1_000, 3, 1), 333); //rounds down
+        assert_eq!(average_cost(u64::MAX, u64::MAX, u64::MAX - 1), u64::MAX - 1);
+    }
+
+    #[test]
+    fn test_refund_amount() {
+        assert_eq!(refund_amount(1_000, 5_000, 4_000), Some(1_000));
+        assert_eq!(refund_amount(1_000, 4_000, 4_000), Some(1_000));
+        assert_eq!(refund_amount(1_000, 2_000, 4_000), Some(500)); //everyone gets the same share
+        assert_eq!(refund_amount(1_000, 0, 4_000), Some(0));
+        assert_eq!(refund_amount(1, 1, 3), Some(0)); //rounds down
+        assert_eq!(refund_amount(u64::MAX, u64::MAX - 1, u64::MAX), Some(u64::MAX - 1));
+    }
+
+    #[test]
+    fn test_verify_merkle_proof() {
+        let wallets: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = wallets.iter().map(|wallet| presale_leaf(wallet, 1_000)).collect();
+        let pair = |a: [u8; 32], b: [u8; 32]| {
+            if a <= b {
+                hashv(&[&a, &b]).to_bytes()
+            } else {
+                hashv(&[&b, &a]).to_bytes()
+            }
+        };
+        let left = pair(leaves[0], leaves[1]);
+        let root = pair(left, leaves[2]);
+
+        assert!(verify_merkle_proof(&[leaves[1], leaves[2]], &root, leaves[0]));
+        assert!(verify_merkle_proof(&[leaves[0], leaves[2]], &root, leaves[1]));
+        assert!(verify_merkle_proof(&[left], &root, leaves[2]));
+
+        assert!(!verify_merkle_proof(&[left], &root, presale_leaf(&wallets[2], 1_001))); //wrong cap
+        assert!(!verify_merkle_proof(&[left], &root, presale_leaf(&Pubkey::new_unique(), 1_000)));
+        assert!(!verify_merkle_proof(&[], &root, leaves[0]));
+    }
+
+    #[test]
+    fn test_vested_amount() {
+        let total = 100_000_000_000_000;
+        assert_eq!(vested_amount(total, -1, 0, 1000), 0);
+        assert_eq!(vested_amount(total, 0, 0, 1000), 0);
+        assert_eq!(vested_amount(total, 249, 250, 1000), 0); //before cliff
+        assert_eq!(vested_amount(total, 250, 250, 1000), total / 4); //cliff releases what accrued
+        assert_eq!(vested_amount(total, 500, 250, 1000), total / 2);
+        assert_eq!(vested_amount(total, 1000, 250, 1000), total);
+        assert_eq!(vested_amount(total, i64::MAX, 250, 1000), total);
+        assert_eq!(vested_amount(u64::MAX, 999, 0, 1000), (u64::MAX as u128 * 999 / 1000) as u64);
+    }
+
+    #[test]
+    fn test_sqrt_price_x64() {
+        assert_eq!(sqrt_price_x64(1, 1), Some(1 << 64)); //price 1
+        assert_eq!(sqrt_price_x64(4, 1), Some(2 << 64)); //price 4
+        assert_eq!(sqrt_price_x64(1, 4), Some(1 << 63)); //price 0.25
+        assert_eq!(sqrt_price_x64(1, 0), None);
+    }
+
+    #[test]
+    fn test_liquidity_for_amounts() {
+        let sqrt_price = 1 << 64; //price 1
+        let sqrt_price_lower = 1 << 63; //price 0.25
+        let sqrt_price_upper = 2 << 64; //price 4
+
+        //amount_a * sqrt_price * sqrt_price_upper / (sqrt_price_upper - sqrt_price) = 2000
+        //amount_b / (sqrt_price - sqrt_price_lower) = 2000
+        assert_eq!(liquidity_for_amounts(sqrt_price, sqrt_price_lower, sqrt_price_upper, 1000, 1000), Some(2000));
+        //the smaller side limits the liquidity
+        assert_eq!(liquidity_for_amounts(sqrt_price, sqrt_price_lower, sqrt_price_upper, 1000, 100), Some(200));
+        //price outside of the range
+        assert_eq!(liquidity_for_amounts(sqrt_price_upper, sqrt_price_lower, sqrt_price_upper, 1000, 1000), None);
+    }
+
+    #[test]
+    fn test_check_metadata_fields() {
+        assert!(check_metadata_fields(&[]).is_ok());
+        assert!(check_metadata_fields(&[
+            (Field::Name, "a".repeat(MAX_NAME_LENGTH)),
+            (Field::Symbol, "a".repeat(MAX_SYMBOL_LENGTH)),
+            (Field::Uri, "a".repeat(MAX_URI_LENGTH)),
+            (Field::Key("website".to_string()), "a".repeat(MAX_METADATA_VALUE_LENGTH)),
+        ])
+        .is_ok());
+        assert!(check_metadata_fields(&[(Field::Name, "a".repeat(MAX_NAME_LENGTH + 1))]).is_err());
+        assert!(check_metadata_fields(&[(Field::Symbol, "a".repeat(MAX_SYMBOL_LENGTH + 1))]).is_err());
+        assert!(check_metadata_fields(&[(Field::Uri, "a".repeat(MAX_URI_LENGTH + 1))]).is_err());
+        assert!(check_metadata_fields(&[(Field::Key(String::new()), "a".to_string())]).is_err());
+        assert!(check_metadata_fields(&[(Field::Key("a".repeat(MAX_METADATA_KEY_LENGTH + 1)), "a".to_string())]).is_err());
+        assert!(check_metadata_fields(&[(Field::Key("twitter".to_string()), "a".repeat(MAX_METADATA_VALUE_LENGTH + 1))]).is_err());
+    }
+}
@@ -1,11 +1,176 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{self as token, Mint, TokenInterface, TokenAccount, TransferChecked},
+    token_interface::{Mint, TokenInterface, TokenAccount},
 };
 
 use crate::{
     state::{BondingCurve, Global, LastWithdraw},
-    CurveLaunchpadError,
+    quote_account, quote_accounts, transfer_checked_with_hook, transfer_quote, CurveLaunchpadError, WithdrawEvent, WITHDRAW_COOLDOWN_SECONDS,
 };
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    user: Signer<'info>,
+
+    #[account(
+        seeds = [Global::SEED_PREFIX],
+        bump,
+    )]
+    global: Box<Account<'info, Global>>,
+
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + LastWithdraw::INIT_SPACE,
+        seeds = [LastWithdraw::SEED_PREFIX, bonding_curve.key().as_ref()],
+        bump,
+        payer = user,
+    )]
+    last_withdraw: Box<Account<'info, LastWithdraw>>,
+
+    #[account(
+        mut,
+        address = bonding_curve.token_account,
+    )]
+    bonding_curve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    system_program: Program<'info, System>,
+
+    token_program: Interface<'info, TokenInterface>,
+
+    associated_token_program: Program<'info, AssociatedToken>,
+
+    //only for curves priced in an SPL quote mint
+    #[account(address = bonding_curve.quote_mint @ CurveLaunchpadError::InvalidQuoteAccount)]
+    quote_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        address = bonding_curve.quote_vault @ CurveLaunchpadError::InvalidQuoteAccount,
+    )]
+    quote_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    user_quote_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    quote_token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn withdraw<'info>(ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>) -> Result<()> {
+    //confirm program is initialized
+    require!(
+        ctx.accounts.global.initialized,
+        CurveLaunchpadError::NotInitialized
+    );
+
+    //confirm user is the withdraw authority
+    require!(
+        ctx.accounts.user.key == &ctx.accounts.global.withdraw_authority,
+        CurveLaunchpadError::InvalidWithdrawAuthority,
+    );
+
+    //bonding curve must be complete
+    require!(
+        ctx.accounts.bonding_curve.complete,
+        CurveLaunchpadError::BondingCurveNotComplete,
+    );
+
+    //the reserves go to the whirlpool first, only what it left behind is withdrawn. Curves that
+    //can't migrate, e.g. hooked mints without a token badge, are frozen by the guardian instead
+    require!(
+        ctx.accounts.bonding_curve.migrated || ctx.accounts.bonding_curve.frozen,
+        CurveLaunchpadError::BondingCurveNotMigrated,
+    );
+
+    //withdrawals are rate limited per curve
+    let timestamp = Clock::get()?.unix_timestamp;
+    require!(
+        timestamp - ctx.accounts.last_withdraw.last_withdraw_timestamp >= WITHDRAW_COOLDOWN_SECONDS,
+        CurveLaunchpadError::WithdrawCooldownNotMet,
+    );
+
+    //transfer SPL
+    let token_amount = ctx.accounts.bonding_curve_token_account.amount;
+
+    let signer: [&[&[u8]]; 1] = [&[
+        BondingCurve::SEED_PREFIX,
+        ctx.accounts.bonding_curve.mint.as_ref(),
+        &[ctx.bumps.bonding_curve],
+    ]];
+
+    transfer_checked_with_hook(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.bonding_curve_token_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.bonding_curve.to_account_info(),
+        ctx.remaining_accounts,
+        token_amount,
+        ctx.accounts.mint.decimals,
+        &signer,
+    )?;
+
+    //transfer SOL, the bonding curve keeps its rent exempt balance
+    let sol_amount = ctx.accounts.bonding_curve.real_sol_reserves;
+    if ctx.accounts.bonding_curve.is_native_quote() {
+        let from_account = &ctx.accounts.bonding_curve;
+        let to_account = &ctx.accounts.user;
+
+        **from_account.to_account_info().try_borrow_mut_lamports()? -= sol_amount;
+        **to_account.try_borrow_mut_lamports()? += sol_amount;
+    } else {
+        let (quote_mint, quote_vault, quote_token_program) = quote_accounts(
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.quote_token_program,
+        )?;
+        let user_quote_account = quote_account(&ctx.accounts.user_quote_account, &quote_mint.key(), ctx.accounts.user.key)?;
+
+        transfer_quote(
+            quote_token_program.to_account_info(),
+            quote_vault.to_account_info(),
+            quote_mint,
+            user_quote_account.to_account_info(),
+            ctx.accounts.bonding_curve.to_account_info(),
+            sol_amount,
+            &signer,
+        )?;
+    }
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.real_sol_reserves = 0;
+    bonding_curve.real_token_reserves = 0;
+
+    ctx.accounts.last_withdraw.last_withdraw_timestamp = timestamp;
+
+    emit_cpi!(WithdrawEvent {
+        user: *ctx.accounts.user.to_account_info().key,
+        mint: *ctx.accounts.mint.to_account_info().key,
+        bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
+        sol_amount,
+        token_amount,
+        timestamp,
+    });
+
+    Ok(())
+}
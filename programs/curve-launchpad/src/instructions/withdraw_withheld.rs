@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022_extensions::{withdraw_withheld_tokens_from_mint, WithdrawWithheldTokensFromMint},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    state::{BondingCurve, CreatorVault},
+    CurveLaunchpadError, WithdrawWithheldEvent,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawWithheld<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    #[account(mut)]
+    mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Using seed to validate mint_authority account, the withdraw authority of the transfer fees
+    #[account(
+        seeds=[b"mint-authority"],
+        bump,
+    )]
+    mint_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [BondingCurve::SEED_PREFIX, mint.key().as_ref()],
+        bump,
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        seeds = [CreatorVault::SEED_PREFIX, bonding_curve.key().as_ref()],
+        bump,
+    )]
+    creator_vault: Box<Account<'info, CreatorVault>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = creator_vault,
+        associated_token::token_program = token_program,
+    )]
+    creator_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    system_program: Program<'info, System>,
+
+    token_program: Interface<'info, TokenInterface>,
+
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn withdraw_withheld(ctx: Context<WithdrawWithheld>) -> Result<()> {
+    let amount_before = ctx.accounts.creator_vault_token_account.amount;
+
+    let seeds = &["mint-authority".as_bytes(), &[ctx.bumps.mint_authority]];
+    let signer = [&seeds[..]];
+
+    //anyone can crank it, the fees only ever go to the creator vault
+    withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        WithdrawWithheldTokensFromMint {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            destination: ctx.accounts.creator_vault_token_account.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        },
+        &signer,
+    ))?;
+
+    ctx.accounts.creator_vault_token_account.reload()?;
+    let token_amount = ctx.accounts.creator_vault_token_account.amount - amount_before;
+
+    require!(token_amount > 0, CurveLaunchpadError::NoWithheldFeesToWithdraw);
+
+    emit_cpi!(WithdrawWithheldEvent {
+        mint: *ctx.accounts.mint.to_account_info().key,
+        bonding_curve: *ctx.accounts.bonding_curve.to_account_info().key,
+        creator_vault: *ctx.accounts.creator_vault.to_account_info().key,
+        token_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -9,59 +9,8 @@ use anchor_lang::solana_program::{
 pub mod instructions;
 pub mod state;
 pub mod amm;
-pub use state::{BondingCurve, Team};
-use anchor_lang::{
-    prelude::*,
-    system_program::{create_account, CreateAccount},
-};
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_interface::{Mint, TokenAccount, TokenInterface},
-};
-use spl_tlv_account_resolution::{
-    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
-};
-use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
-
-// Order of accounts matters for this struct.
-// The first 4 accounts are the accounts required for token transfer (source, mint, destination, owner)
-// Remaining accounts are the extra accounts required from the ExtraAccountMetaList account
-// These accounts are provided via CPI to this program from the token2022 program
-#[derive(Accounts)]
-pub struct TransferHook<'info> {
-    #[account(
-        token::mint = mint, 
-        token::authority = owner,
-    )]
-    pub source_token: Box<InterfaceAccount<'info, TokenAccount>>,
-    pub mint: Box<InterfaceAccount<'info, Mint>>,
-    #[account(
-        token::mint = mint,
-    )]
-    pub destination_token: Box<InterfaceAccount<'info, TokenAccount>>,
-    /// CHECK: source token account owner, can be SystemAccount or PDA owned by another program
-    pub owner: UncheckedAccount<'info>,
-    /// CHECK: ExtraAccountMetaList Account,
-    #[account(
-        seeds = [b"extra-account-metas", mint.key().as_ref()], 
-        bump
-    )]
-    pub extra_account_meta_list: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        seeds = [b"delegate"], 
-        bump
-    )]
-    pub delegate: SystemAccount<'info>,
-    #[account(mut, seeds = [b"user", owner.key().as_ref(), mint.key().as_ref()], bump)]
-    pub user: SystemAccount<'info>,
-    pub system_program: Program<'info, System>,
-    #[account(mut,
-        seeds = [BondingCurve::SEED_PREFIX, bonding_curve.creator.as_ref()],
-        bump,
-    )]
-    bonding_curve: Box<Account<'info, BondingCurve>>,
-}
+pub use state::{BondingCurve, CurveKind, GraduationTarget, LaunchFeeSchedule, LaunchParams, LaunchParamsLimits, Presale, RateLimitPolicy, SoftCap, Team, TransferFeeParams, VestingParams};
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
 
 declare_id!("FYnpDiZVejAbvnme7WZrxUE2T5K4Fv4MwDsZQ2JLzMYm");
 
@@ -70,55 +19,10 @@ pub mod curve_launchpad {
 
     use super::*;
 
-   /*
     pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
-        let signer_seeds: &[&[&[u8]]] = &[&[b"delegate", &[ctx.bumps.delegate]]];
-    
-        // Anti-bot measures
-        let clock = Clock::get()?;
-        let current_timestamp = clock.unix_timestamp;
-    
-        // Get or initialize the user's transfer data
-        let mut user_transfer_data = UserTransferData::get_or_init(
-            &ctx.accounts.user.to_account_info(),
-            &ctx.accounts.delegate.to_account_info(),
-            &ctx.accounts.system_program.to_account_info(),
-            signer_seeds,
-        )?;
-    
-        // Check if the user is a dev
-        let is_dev = ctx.accounts.bonding_curve.creator == ctx.accounts.user.key();
-    
-        // Calculate the maximum allowed transfer amount
-        let max_transfer_percentage = if is_dev {
-            let seconds_since_last_transfer = (current_timestamp - user_transfer_data.last_transfer_timestamp) as f64;
-            // This is in seconds
-            let max_percentage = (seconds_since_last_transfer / 3600.0) * 0.005;
-            max_percentage.min(0.005) // Cap at 0.5% maximum
-        } else {
-            1.0 // 100% for regular users
-        };
-    
-        let total_supply = ctx.accounts.bonding_curve.token_total_supply;
-        let max_allowed_amount = (total_supply as f64 * max_transfer_percentage) as u64;
-    
-        // Check if the transfer amount exceeds the maximum allowed
-        if amount > max_allowed_amount {
-            return Err(ProgramError::InvalidInstructionData.into());
-        }
-    
-        // Update user's transfer data
-        user_transfer_data.last_transfer_timestamp = current_timestamp;
-        user_transfer_data.save(
-            &ctx.accounts.user.to_account_info(),
-            &ctx.accounts.system_program.to_account_info(),
-            signer_seeds,
-        )?;
-    
-        // Proceed with the transfer if all checks pass
-        Ok(())
-    }
- 
+        transfer_hook::transfer_hook(ctx, amount)
+    }
+
     // fallback instruction handler as workaround to anchor instruction discriminator check
     pub fn fallback<'info>(
         program_id: &Pubkey,
@@ -136,41 +40,170 @@ pub mod curve_launchpad {
                 // invoke custom transfer hook instruction on our program
                 __private::__global::transfer_hook(program_id, accounts, &amount_bytes)
             }
-            _ => return Err(ProgramError::InvalidInstructionData.into()),
+            _ => Err(ProgramError::InvalidInstructionData.into()),
         }
     }
-*/
+
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         initialize::initialize(ctx)
     }
 
-    pub fn create(ctx: Context<Create>, name: String, symbol: String, uri: String, team: Team) -> Result<()> {
-        create::create(ctx, name, symbol, uri, team)
+    pub fn create<'info>(ctx: Context<'_, '_, '_, 'info, Create<'info>>, name: String, symbol: String, uri: String, team: Team, curve_kind: CurveKind, rate_limit_policy: RateLimitPolicy, vesting_params: Option<VestingParams>, initial_buy: Option<InitialBuy>, launch_fee_schedule: Option<LaunchFeeSchedule>, max_wallet_basis_points: u64, presale: Option<Presale>, soft_cap: Option<SoftCap>, launch_params: Option<LaunchParams>, transfer_fee: TransferFeeParams, additional_metadata: Vec<MetadataField>, transfer_hook: bool) -> Result<()> {
+        create::create(ctx, name, symbol, uri, team, curve_kind, rate_limit_policy, vesting_params, initial_buy, launch_fee_schedule, max_wallet_basis_points, presale, soft_cap, launch_params, transfer_fee, additional_metadata, transfer_hook)
     }
 
-    pub fn buy(ctx: Context<Buy>, token_amount: u64, max_sol_cost: u64) -> Result<()> {
-        buy::buy(ctx, token_amount, max_sol_cost)
+    pub fn buy<'info>(ctx: Context<'_, '_, '_, 'info, Buy<'info>>, token_amount: u64, max_sol_cost: u64, presale_proof: Option<PresaleProof>) -> Result<()> {
+        buy::buy(ctx, token_amount, max_sol_cost, presale_proof)
     }
 
-    pub fn sell(ctx: Context<Sell>, token_amount: u64, min_sol_output: u64) -> Result<()> {
+    pub fn buy_exact_in<'info>(ctx: Context<'_, '_, '_, 'info, Buy<'info>>, sol_amount: u64, min_token_output: u64, presale_proof: Option<PresaleProof>) -> Result<()> {
+        buy::buy_exact_in(ctx, sol_amount, min_token_output, presale_proof)
+    }
+
+    pub fn sell<'info>(ctx: Context<'_, '_, '_, 'info, Sell<'info>>, token_amount: u64, min_sol_output: u64) -> Result<()> {
         sell::sell(ctx, token_amount, min_sol_output)
     }
 
+    pub fn sell_exact_out<'info>(ctx: Context<'_, '_, '_, 'info, Sell<'info>>, sol_amount: u64, max_token_input: u64) -> Result<()> {
+        sell::sell_exact_out(ctx, sol_amount, max_token_input)
+    }
+
+    pub fn refund<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>, token_amount: u64) -> Result<()> {
+        refund::refund(ctx, token_amount)
+    }
+
+    pub fn quote_buy(ctx: Context<Quote>, token_amount: u64) -> Result<BuyQuote> {
+        quote::quote_buy(ctx, token_amount)
+    }
+
+    pub fn quote_sell(ctx: Context<Quote>, token_amount: u64) -> Result<SellQuote> {
+        quote::quote_sell(ctx, token_amount)
+    }
+
+    pub fn claim_vested<'info>(ctx: Context<'_, '_, '_, 'info, ClaimVested<'info>>) -> Result<()> {
+        claim_vested::claim_vested(ctx)
+    }
+
+    pub fn claim_creator_fees<'info>(ctx: Context<'_, '_, '_, 'info, ClaimCreatorFees<'info>>) -> Result<()> {
+        claim_creator_fees::claim_creator_fees(ctx)
+    }
+
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        claim_referral_fees::claim_referral_fees(ctx)
+    }
+
+    pub fn harvest_withheld<'info>(ctx: Context<'_, '_, '_, 'info, HarvestWithheld<'info>>) -> Result<()> {
+        harvest_withheld::harvest_withheld(ctx)
+    }
+
+    pub fn withdraw_withheld(ctx: Context<WithdrawWithheld>) -> Result<()> {
+        withdraw_withheld::withdraw_withheld(ctx)
+    }
+
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadata>,
+        name: Option<String>,
+        symbol: Option<String>,
+        uri: Option<String>,
+        additional_metadata: Vec<MetadataField>,
+    ) -> Result<()> {
+        update_metadata::update_metadata(ctx, name, symbol, uri, additional_metadata)
+    }
+
+    pub fn revoke_metadata_authority(ctx: Context<RevokeMetadataAuthority>) -> Result<()> {
+        revoke_metadata_authority::revoke_metadata_authority(ctx)
+    }
+
+    pub fn withdraw<'info>(ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>) -> Result<()> {
+        withdraw::withdraw(ctx)
+    }
+
+    pub fn migrate_to_whirlpool<'info>(ctx: Context<'_, '_, '_, 'info, MigrateToWhirlpool<'info>>) -> Result<()> {
+        migrate::migrate_to_whirlpool(ctx)
+    }
+
+    pub fn migrate_legacy_bonding_curve<'info>(ctx: Context<'_, '_, '_, 'info, MigrateLegacyBondingCurve<'info>>) -> Result<()> {
+        migrate_legacy::migrate_legacy_bonding_curve(ctx)
+    }
+
+    pub fn migrate_global(ctx: Context<MigrateGlobal>) -> Result<()> {
+        migrate_global::migrate_global(ctx)
+    }
+
+    pub fn migrate_user_transfer_data(ctx: Context<MigrateUserTransferData>) -> Result<()> {
+        migrate_user_transfer_data::migrate_user_transfer_data(ctx)
+    }
+
+    pub fn init_user_transfer_data(ctx: Context<InitUserTransferData>) -> Result<()> {
+        init_user_transfer_data::init_user_transfer_data(ctx)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        propose_authority::propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        accept_authority::accept_authority(ctx)
+    }
+
+    pub fn set_launch_config(
+        ctx: Context<SetLaunchConfig>,
+        index: u16,
+        fee_recipient: Pubkey,
+        fee_basis_points: u64,
+        launch_params: LaunchParams,
+        launch_fee_schedule: LaunchFeeSchedule,
+        quote_mint: Pubkey,
+        graduation_target: GraduationTarget,
+    ) -> Result<()> {
+        set_launch_config::set_launch_config(ctx, index, fee_recipient, fee_basis_points, launch_params, launch_fee_schedule, quote_mint, graduation_target)
+    }
+
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        set_guardian::set_guardian(ctx, guardian)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        set_paused::set_paused(ctx, paused)
+    }
+
+    pub fn set_frozen(ctx: Context<SetFrozen>, frozen: bool) -> Result<()> {
+        set_frozen::set_frozen(ctx, frozen)
+    }
+
     pub fn set_params(
         ctx: Context<SetParams>,
+        fee_recipient: Pubkey,
+        withdraw_authority: Pubkey,
         initial_virtual_token_reserves: u64,
         initial_virtual_sol_reserves: u64,
         initial_real_token_reserves: u64,
         inital_token_supply: u64,
         fee_basis_points: u64,
+        creator_fee_share_basis_points: u64,
+        referral_fee_share_basis_points: u64,
+        launch_fee_schedule: LaunchFeeSchedule,
+        graduation_target: GraduationTarget,
+        launch_params_limits: LaunchParamsLimits,
+        max_transfer_fee: TransferFeeParams,
+        max_launch_fee_basis_points: u64,
     ) -> Result<()> {
         set_params::set_params(
             ctx,
+            fee_recipient,
+            withdraw_authority,
             initial_virtual_token_reserves,
             initial_virtual_sol_reserves,
             initial_real_token_reserves,
             inital_token_supply,
             fee_basis_points,
+            creator_fee_share_basis_points,
+            referral_fee_share_basis_points,
+            launch_fee_schedule,
+            graduation_target,
+            launch_params_limits,
+            max_transfer_fee,
+            max_launch_fee_basis_points,
         )
     }
 }
\ No newline at end of file
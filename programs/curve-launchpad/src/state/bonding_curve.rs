@@ -1,6 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token::native_mint;
 use std::fmt;
 
+use crate::{
+    amm::{mul_div_ceil, new_curve, Curve, CurveReserves},
+    launch_fee_basis_points,
+    state::{GraduationTarget, LaunchFeeSchedule, LaunchFeeUnit, LaunchParams},
+    DEFAULT_CREATOR_TRANSFER_LIMIT_BASIS_POINTS, DEFAULT_TRANSFER_WINDOW_SECONDS, DEFAULT_USER_TRANSFER_LIMIT_BASIS_POINTS,
+};
+
 #[account]
 #[derive(InitSpace)]
 pub struct BondingCurve {
@@ -13,12 +21,114 @@ pub struct BondingCurve {
     pub creator: Pubkey,
     pub team: Team,
     pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub curve_kind: CurveKind,
+    pub frozen: bool,
+    pub rate_limit_policy: RateLimitPolicy,
+    pub launch_slot: u64,
+    pub launch_timestamp: i64,
+    pub launch_fee_schedule: LaunchFeeSchedule,
+    /// Most a wallet may hold after a buy, in basis points of the total supply, 0 for no cap.
+    pub max_wallet_basis_points: u64,
+    pub presale: Option<Presale>,
+    pub soft_cap: Option<SoftCap>,
+    /// Set once a soft-cap curve missed its cap, holders can only refund from then on.
+    pub failed: bool,
+    /// Mint the curve is priced in, `Pubkey::default()` for native SOL.
+    /// The `*_sol_reserves` are denominated in it.
+    pub quote_mint: Pubkey,
+    /// Token account of the curve holding its quote reserves, unused for native SOL.
+    pub quote_vault: Pubkey,
+    /// Launch params the curve was created with, later `set_params` calls don't reprice it.
+    pub launch_params: LaunchParams,
+    /// Launch config the curve was created under, `Pubkey::default()` for `Global`.
+    pub launch_config: Pubkey,
+    /// Set once the liquidity moved to the whirlpool, a curve only migrates once.
+    pub migrated: bool,
+    /// SOL still held for soft-cap deposits, refunds are paid pro-rata against it.
+    pub deposited_sol_amount: u64,
+}
+
+/// Minimum `real_sol_reserves`, in the curve's quote mint, a curve has to raise before `deadline`, or it fails.
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct SoftCap {
+    pub min_real_sol_reserves: u64,
+    pub deadline: i64,
+}
+
+/// Allowlisted phase before public trading. Leaves of the Merkle tree are
+/// `keccak(wallet || max_token_amount as u64 le)`.
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct Presale {
+    pub merkle_root: [u8; 32],
+    pub end_timestamp: i64,
+}
+
+/// Layout of bonding curves created before curves were seeded by mint,
+/// when the PDA was derived from `[SEED_PREFIX, creator]`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyBondingCurve {
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub complete: bool,
+    pub creator: Pubkey,
+    pub team: Team,
+    pub token_account: Pubkey,
 }
+
 #[account]
 
 #[derive(InitSpace, Default)]
 pub struct UserTransferData {
     pub last_transfer_timestamp: i64,
+    /// Tokens left to move in the current window, refilled linearly over time.
+    pub available_amount: u64,
+    /// Tokens bought during the presale, counted against the wallet's allowlisted cap.
+    pub presale_purchased_amount: u64,
+}
+
+/// Layout of user transfer data created before the bucket and presale fields were appended.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyUserTransferData {
+    pub last_transfer_timestamp: i64,
+}
+
+/// How many tokens a wallet may move per window, in basis points of the total supply.
+/// A limit of 0 means unlimited.
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct RateLimitPolicy {
+    pub creator_limit_basis_points: u64,
+    pub user_limit_basis_points: u64,
+    pub window_seconds: i64,
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        RateLimitPolicy {
+            creator_limit_basis_points: DEFAULT_CREATOR_TRANSFER_LIMIT_BASIS_POINTS,
+            user_limit_basis_points: DEFAULT_USER_TRANSFER_LIMIT_BASIS_POINTS,
+            window_seconds: DEFAULT_TRANSFER_WINDOW_SECONDS,
+        }
+    }
+}
+
+impl RateLimitPolicy {
+    pub fn is_valid(&self) -> bool {
+        self.creator_limit_basis_points <= 10000
+            && self.user_limit_basis_points <= 10000
+            && self.window_seconds > 0
+    }
+
+    pub fn limit_basis_points(&self, is_creator: bool) -> u64 {
+        if is_creator {
+            self.creator_limit_basis_points
+        } else {
+            self.user_limit_basis_points
+        }
+    }
 }
 #[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, InitSpace, Default)]
 pub enum Team {
@@ -27,15 +137,96 @@ pub enum Team {
     Red,
 }
 
+/// Shape of the price curve, see `amm::curve`.
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Default)]
+pub enum CurveKind {
+    #[default]
+    ConstantProduct,
+    Linear,
+    Exponential,
+    Stepped,
+}
+
 impl BondingCurve {
     pub const SEED_PREFIX: &'static [u8; 13] = b"bonding-curve";
+
+    pub fn curve(&self) -> Box<dyn Curve> {
+        new_curve(
+            self.curve_kind,
+            self.virtual_sol_reserves as u128,
+            self.virtual_token_reserves as u128,
+            self.real_sol_reserves as u128,
+            self.real_token_reserves as u128,
+            self.launch_params.initial_virtual_token_reserves as u128,
+        )
+    }
+
+    pub fn set_reserves(&mut self, reserves: CurveReserves) {
+        self.virtual_sol_reserves = reserves.virtual_sol_reserves as u64;
+        self.virtual_token_reserves = reserves.virtual_token_reserves as u64;
+        self.real_sol_reserves = reserves.real_sol_reserves as u64;
+        self.real_token_reserves = reserves.real_token_reserves as u64;
+    }
+
+    /// Trading fee at `clock`, following the launch fee schedule down to `base_fee_basis_points`.
+    pub fn fee_basis_points(&self, base_fee_basis_points: u64, clock: &Clock) -> u64 {
+        let elapsed = match self.launch_fee_schedule.unit {
+            LaunchFeeUnit::Seconds => clock.unix_timestamp.saturating_sub(self.launch_timestamp).max(0) as u64,
+            LaunchFeeUnit::Slots => clock.slot.saturating_sub(self.launch_slot),
+        };
+
+        launch_fee_basis_points(base_fee_basis_points, &self.launch_fee_schedule, elapsed)
+    }
+
+    /// Tokens a buy of `token_amount` reaching `target` is clamped to, None when it falls short
+    /// or the curve sells out its real token reserves.
+    pub fn tokens_to_graduate(&self, curve: &dyn Curve, target: GraduationTarget, token_amount: u64) -> Option<u64> {
+        let tokens = match target {
+            GraduationTarget::TokenExhaustion => None,
+            GraduationTarget::RealSolReserves(real_sol_reserves) => {
+                curve.tokens_to_reach_real_sol(real_sol_reserves as u128, token_amount as u128)
+            }
+            GraduationTarget::MarketCap(market_cap) => {
+                let price = mul_div_ceil(market_cap as u128, 1 << 64, self.token_total_supply as u128)?;
+                curve.tokens_to_reach_price(price, token_amount as u128)
+            }
+        };
+
+        tokens.map(|tokens| tokens as u64)
+    }
+
+    pub fn is_native_quote(&self) -> bool {
+        self.quote_mint == Pubkey::default()
+    }
+
+    /// Mint the curve's whirlpool pairs it against, wrapped SOL for curves priced in SOL.
+    pub fn pool_quote_mint(&self) -> Pubkey {
+        if self.is_native_quote() {
+            native_mint::ID
+        } else {
+            self.quote_mint
+        }
+    }
+
+    /// Whether the curve failed, or missed its soft cap by `timestamp`.
+    pub fn soft_cap_failed(&self, timestamp: i64) -> bool {
+        self.failed
+            || (!self.complete
+                && self.soft_cap.is_some_and(|soft_cap| {
+                    timestamp >= soft_cap.deadline && self.real_sol_reserves < soft_cap.min_real_sol_reserves
+                }))
+    }
+
+    pub fn presale_active(&self, timestamp: i64) -> bool {
+        self.presale.is_some_and(|presale| timestamp < presale.end_timestamp)
+    }
 }
 
 impl fmt::Display for BondingCurve {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "virtual_sol_reserves: {}, virtual_token_reserves: {}, real_sol_reserves: {}, real_token_reserves: {}, token_total_supply: {}, complete: {}, creator: {:?}",
+            "virtual_sol_reserves: {}, virtual_token_reserves: {}, real_sol_reserves: {}, real_token_reserves: {}, token_total_supply: {}, complete: {}, creator: {:?}, curve_kind: {:?}",
             self.virtual_sol_reserves,
             self.virtual_token_reserves,
             self.real_sol_reserves,
@@ -43,6 +234,7 @@ impl fmt::Display for BondingCurve {
             self.token_total_supply,
             self.complete,
             self.creator,
+            self.curve_kind,
         )
     }
 }
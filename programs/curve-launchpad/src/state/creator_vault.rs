@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+/// Holds the creator's share of the trading fees of a bonding curve until claimed.
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorVault {
+    pub bonding_curve: Pubkey,
+}
+
+impl CreatorVault {
+    pub const SEED_PREFIX: &'static [u8; 13] = b"creator-vault";
+}
@@ -15,8 +15,203 @@ pub struct Global {
     pub initial_token_supply: u64,
     pub fee_basis_points: u64,
     pub withdraw_authority: Pubkey,
+    /// Share of the trading fee paid to the curve creator, in basis points of the fee.
+    pub creator_fee_share_basis_points: u64,
+    /// Share of the trading fee paid to the referrer of a trade, in basis points of the fee.
+    pub referral_fee_share_basis_points: u64,
+    /// Proposed by the authority, takes over once it accepts.
+    pub pending_authority: Option<Pubkey>,
+    /// Can pause the program and freeze bonding curves.
+    pub guardian: Pubkey,
+    pub paused: bool,
+    /// Anti-snipe fee schedule given to new curves, unless overridden at `create`.
+    pub launch_fee_schedule: LaunchFeeSchedule,
+    /// When a curve completes and moves on to migration.
+    pub graduation_target: GraduationTarget,
+    /// Bounds on the launch params a creator may pick at `create`.
+    pub launch_params_limits: LaunchParamsLimits,
+    /// Highest Token-2022 transfer fee a creator may set at `create`.
+    pub max_transfer_fee: TransferFeeParams,
+    /// Highest initial launch fee a creator or launch config may set.
+    pub max_launch_fee_basis_points: u64,
+}
+
+/// Layout of the global account before any fields were appended to it.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyGlobal {
+    pub authority: Pubkey,
+    pub initialized: bool,
+    pub fee_recipient: Pubkey,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub initial_real_sol_reserves: u64,
+    pub initial_token_supply: u64,
+    pub fee_basis_points: u64,
+    pub withdraw_authority: Pubkey,
+}
+
+/// Token-2022 transfer fee of a mint, fixed at `create`.
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct TransferFeeParams {
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+/// Reserves and supply a curve launches with, snapshotted into the curve at `create`.
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct LaunchParams {
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub initial_token_supply: u64,
+}
+
+/// Inclusive bounds on each of the launch params, both zero unless set by the authority.
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct LaunchParamsLimits {
+    pub min: LaunchParams,
+    pub max: LaunchParams,
+}
+
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum GraduationTarget {
+    /// Once the real token reserves are sold out.
+    #[default]
+    TokenExhaustion,
+    /// Once the real SOL reserves reach this many lamports.
+    RealSolReserves(u64),
+    /// Once the spot price times the total supply reaches this many lamports.
+    MarketCap(u64),
+}
+
+/// Elevated fee right after launch, decaying to the base fee over `duration`
+/// seconds or slots. A zero duration disables it.
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct LaunchFeeSchedule {
+    pub initial_fee_basis_points: u64,
+    pub duration: u64,
+    pub unit: LaunchFeeUnit,
+    pub decay: LaunchFeeDecay,
+}
+
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum LaunchFeeUnit {
+    #[default]
+    Seconds,
+    Slots,
+}
+
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum LaunchFeeDecay {
+    #[default]
+    Linear,
+    /// The elevated part halves `LAUNCH_FEE_HALVINGS` times over the duration.
+    Exponential,
+}
+
+impl LaunchFeeSchedule {
+    pub fn is_valid(&self) -> bool {
+        self.initial_fee_basis_points <= 10000
+    }
+
+    pub fn is_within(&self, max_fee_basis_points: u64) -> bool {
+        self.initial_fee_basis_points <= max_fee_basis_points
+    }
+}
+
+impl LaunchParams {
+    /// The real token reserves are sold out of the supply, below the virtual reserves.
+    pub fn is_valid(&self) -> bool {
+        self.initial_virtual_sol_reserves > 0
+            && self.initial_real_token_reserves > 0
+            && self.initial_real_token_reserves <= self.initial_token_supply
+            && self.initial_real_token_reserves < self.initial_virtual_token_reserves
+    }
+}
+
+impl LaunchParamsLimits {
+    pub fn is_valid(&self) -> bool {
+        self.min.initial_virtual_token_reserves <= self.max.initial_virtual_token_reserves
+            && self.min.initial_virtual_sol_reserves <= self.max.initial_virtual_sol_reserves
+            && self.min.initial_real_token_reserves <= self.max.initial_real_token_reserves
+            && self.min.initial_token_supply <= self.max.initial_token_supply
+    }
+
+    pub fn contains(&self, params: &LaunchParams) -> bool {
+        (self.min.initial_virtual_token_reserves..=self.max.initial_virtual_token_reserves)
+            .contains(&params.initial_virtual_token_reserves)
+            && (self.min.initial_virtual_sol_reserves..=self.max.initial_virtual_sol_reserves)
+                .contains(&params.initial_virtual_sol_reserves)
+            && (self.min.initial_real_token_reserves..=self.max.initial_real_token_reserves)
+                .contains(&params.initial_real_token_reserves)
+            && (self.min.initial_token_supply..=self.max.initial_token_supply)
+                .contains(&params.initial_token_supply)
+    }
+}
+
+impl TransferFeeParams {
+    pub fn is_valid(&self) -> bool {
+        self.transfer_fee_basis_points <= 10000
+    }
+
+    pub fn is_within(&self, max: &TransferFeeParams) -> bool {
+        self.transfer_fee_basis_points <= max.transfer_fee_basis_points && self.maximum_fee <= max.maximum_fee
+    }
 }
 
 impl Global {
    pub const SEED_PREFIX: &'static [u8; 6] = b"global";
+
+   /// Launch params of curves created without overrides.
+   pub fn launch_params(&self) -> LaunchParams {
+       LaunchParams {
+           initial_virtual_token_reserves: self.initial_virtual_token_reserves,
+           initial_virtual_sol_reserves: self.initial_virtual_sol_reserves,
+           initial_real_token_reserves: self.initial_real_token_reserves,
+           initial_token_supply: self.initial_token_supply,
+       }
+   }
+
+   /// Writes the defaults of the fields appended after `LegacyGlobal` that an account of
+   /// `space` bytes, discriminator excluded, had no room for. Shared by `initialize` and
+   /// `migrate_global` so new fields only get their default here.
+   pub fn set_appended_defaults(&mut self, space: usize) {
+       let mut end = LegacyGlobal::INIT_SPACE;
+       let mut appended = |size: usize| {
+           end += size;
+           end > space
+       };
+
+       if appended(8) {
+           self.creator_fee_share_basis_points = 0;
+       }
+       if appended(8) {
+           self.referral_fee_share_basis_points = 0;
+       }
+       if appended(1 + 32) {
+           self.pending_authority = None;
+       }
+       if appended(32) {
+           self.guardian = self.authority;
+       }
+       if appended(1) {
+           self.paused = false;
+       }
+       if appended(LaunchFeeSchedule::INIT_SPACE) {
+           self.launch_fee_schedule = LaunchFeeSchedule::default();
+       }
+       if appended(GraduationTarget::INIT_SPACE) {
+           self.graduation_target = GraduationTarget::TokenExhaustion;
+       }
+       if appended(LaunchParamsLimits::INIT_SPACE) {
+           self.launch_params_limits = LaunchParamsLimits::default();
+       }
+       if appended(TransferFeeParams::INIT_SPACE) {
+           self.max_transfer_fee = TransferFeeParams::default();
+       }
+       if appended(8) {
+           self.max_launch_fee_basis_points = 0;
+       }
+   }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 
+/// Last withdraw from a bonding curve, seeded by the curve so each one has its own cooldown.
 #[account]
 #[derive(InitSpace)]
 pub struct LastWithdraw {
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{GraduationTarget, LaunchFeeSchedule, LaunchParams};
+
+/// Named set of launch parameters curves can be created under instead of the `Global` ones.
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchConfig {
+    pub index: u16,
+    pub fee_recipient: Pubkey,
+    pub fee_basis_points: u64,
+    pub launch_params: LaunchParams,
+    pub launch_fee_schedule: LaunchFeeSchedule,
+    /// Mint the curves are priced in, the default pubkey for SOL. The launch params and
+    /// graduation target are amounts of it.
+    pub quote_mint: Pubkey,
+    pub graduation_target: GraduationTarget,
+}
+
+impl LaunchConfig {
+    pub const SEED_PREFIX: &'static [u8; 13] = b"launch-config";
+}
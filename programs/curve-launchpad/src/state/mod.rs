@@ -1,7 +1,17 @@
 pub mod global;
 pub mod bonding_curve;
 pub mod last_withdraw;
+pub mod creator_vault;
+pub mod referrer_stats;
+pub mod vesting;
+pub mod user_deposit;
+pub mod launch_config;
 
 pub use global::*;
 pub use bonding_curve::*;
-pub use last_withdraw::*;
\ No newline at end of file
+pub use last_withdraw::*;
+pub use creator_vault::*;
+pub use referrer_stats::*;
+pub use vesting::*;
+pub use user_deposit::*;
+pub use launch_config::*;
\ No newline at end of file
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+/// Running totals, in lamports, of the trades a referrer brought in on curves priced
+/// in SOL. Also holds the referrer's share of the SOL fees until claimed.
+#[account]
+#[derive(InitSpace)]
+pub struct ReferrerStats {
+    pub referrer: Pubkey,
+    pub total_volume: u64,
+    pub total_earned: u64,
+}
+
+impl ReferrerStats {
+    pub const SEED_PREFIX: &'static [u8; 14] = b"referrer-stats";
+}
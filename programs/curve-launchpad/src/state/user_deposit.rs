@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::average_cost;
+
+/// What a wallet paid into a soft-cap curve, refunded at its average price if the curve fails.
+#[account]
+#[derive(InitSpace)]
+pub struct UserDeposit {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+}
+
+impl UserDeposit {
+    pub const SEED_PREFIX: &'static [u8; 12] = b"user-deposit";
+
+    pub fn record_buy(&mut self, token_amount: u64, sol_amount: u64) {
+        self.token_amount = self.token_amount.saturating_add(token_amount);
+        self.sol_amount = self.sol_amount.saturating_add(sol_amount);
+    }
+
+    /// Removes `token_amount` at the average price, returning the SOL it accounted for.
+    pub fn record_sell(&mut self, token_amount: u64) -> u64 {
+        let token_amount = token_amount.min(self.token_amount);
+        let sol_amount = average_cost(self.sol_amount, self.token_amount, token_amount);
+
+        self.token_amount -= token_amount;
+        self.sol_amount -= sol_amount;
+
+        sol_amount
+    }
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+/// Creator allocation carved out of the supply at launch, released linearly after a cliff.
+#[account]
+#[derive(InitSpace)]
+pub struct Vesting {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_timestamp: i64,
+    pub cliff_seconds: i64,
+    pub duration_seconds: i64,
+}
+
+/// Vesting schedule requested at `create`.
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct VestingParams {
+    pub amount: u64,
+    pub cliff_seconds: i64,
+    pub duration_seconds: i64,
+}
+
+impl Vesting {
+    pub const SEED_PREFIX: &'static [u8; 7] = b"vesting";
+}
+
+impl VestingParams {
+    pub fn is_valid(&self) -> bool {
+        self.amount > 0
+            && self.cliff_seconds >= 0
+            && self.duration_seconds > 0
+            && self.cliff_seconds <= self.duration_seconds
+    }
+}
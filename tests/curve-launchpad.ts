@@ -12,6 +12,7 @@ import {
 } from "./util";
 import { createInitializeInstruction, pack, TokenMetadata } from '@solana/spl-token-metadata';
 import { AccountInfo, PublicKey } from '@solana/web3.js';
+import { keccak_256 } from "@noble/hashes/sha3";
 
 import { ACCOUNT_SIZE, ASSOCIATED_TOKEN_PROGRAM_ID, AccountLayout, addExtraAccountMetasForExecute, createExecuteInstruction, createTransferCheckedWithFeeAndTransferHookInstruction, getExtraAccountMetaAddress, getExtraAccountMetas, getMint, getTransferHook, resolveExtraAccountMeta } from '@solana/spl-token';
 import type { Mint } from '@solana/spl-token';
@@ -290,13 +291,22 @@ import {
 
   LENGTH_SIZE,
   MetadataPointerLayout,
+  NATIVE_MINT,
   TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
   TYPE_SIZE,
   createAssociatedTokenAccount,
+  createAssociatedTokenAccountIdempotentInstruction,
   createAssociatedTokenAccountInstruction,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  createTransferCheckedInstruction,
   getAssociatedTokenAddress,
+  getAssociatedTokenAddressSync,
   getOrCreateAssociatedTokenAccount,
   initializeMetadataPointerData,
+  mintTo,
 } from "@solana/spl-token";
 import { BN } from "bn.js";
 import { assert } from "chai";
@@ -306,6 +316,13 @@ import { AMM, calculateFee } from "../client";
 const GLOBAL_SEED = "global";
 const BONDING_CURVE_SEED = "bonding-curve";
 
+const WHIRLPOOL_PROGRAM_ID = new PublicKey("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+const WHIRLPOOLS_CONFIG = new PublicKey("J5T5RStZBW2ayuTp5dGCQMHsUApCReRbytDMRd4ZP2aR");
+const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+const WHIRLPOOL_TICK_SPACING = 256;
+const MAX_TICK_INDEX = 443636;
+const TICK_ARRAY_SIZE = 88;
+
 //TODO: Unit test order is essential, need to refactor to make it so its not.
 
 describe("curve-launchpad", () => {
@@ -316,6 +333,32 @@ describe("curve-launchpad", () => {
   const DEFAULT_INITIAL_VIRTUAL_SOL_RESERVE = 30_000_000_000n;
   const DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE = 1_073_000_000_000_000n;
   const DEFAULT_FEE_BASIS_POINTS = 50n;
+  const NO_LAUNCH_FEE_SCHEDULE = {
+    initialFeeBasisPoints: new BN(0),
+    duration: new BN(0),
+    unit: { seconds: {} },
+    decay: { linear: {} },
+  };
+  const NO_LAUNCH_PARAMS = {
+    initialVirtualTokenReserves: new BN(0),
+    initialVirtualSolReserves: new BN(0),
+    initialRealTokenReserves: new BN(0),
+    initialTokenSupply: new BN(0),
+  };
+  const NO_LAUNCH_PARAMS_LIMITS = {
+    min: NO_LAUNCH_PARAMS,
+    max: NO_LAUNCH_PARAMS,
+  };
+  //no wallet is rate limited, so test curves can be bought out by the creator
+  const NO_RATE_LIMIT_POLICY = {
+    creatorLimitBasisPoints: new BN(0),
+    userLimitBasisPoints: new BN(0),
+    windowSeconds: new BN(3600),
+  };
+  const NO_TRANSFER_FEE = {
+    transferFeeBasisPoints: 0,
+    maximumFee: new BN(0),
+  };
 
   // Configure the client to use the local cluster.
   const provider = anchor.AnchorProvider.env();
@@ -337,7 +380,7 @@ const ata = Keypair.generate()
   );
 
   const [bondingCurvePDA] = pk.findProgramAddressSync(
-    [Buffer.from(BONDING_CURVE_SEED), tokenCreator.publicKey.toBuffer()],
+    [Buffer.from(BONDING_CURVE_SEED), mint.toBuffer()],
     program.programId
   );
   const bondingCurveTokenAccount = PublicKey.findProgramAddressSync(
@@ -349,6 +392,28 @@ const ata = Keypair.generate()
     ASSOCIATED_TOKEN_PROGRAM_ID
 )[0];
 
+  // accounts the mint's transfer hook needs for transfers out of `owner`'s token account
+  const transferHookAccounts = (owner: PublicKey): AccountMeta[] => [
+    {
+      pubkey: pk.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), mint.toBuffer()],
+        program.programId
+      )[0],
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: pk.findProgramAddressSync(
+        [Buffer.from("user"), owner.toBuffer(), mint.toBuffer()],
+        program.programId
+      )[0],
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: bondingCurvePDA, isSigner: false, isWritable: false },
+    { pubkey: program.programId, isSigner: false, isWritable: false },
+  ];
+
   const getAmmFromBondingCurve = async () => {
     let bondingCurveAccount = await program.account.bondingCurve.fetch(
       bondingCurvePDA
@@ -409,7 +474,8 @@ const ata = Keypair.generate()
     user: anchor.web3.Keypair,
     tokenAmount: bigint,
     maxSolAmount: bigint,
-    innerFeeRecipient: anchor.web3.Keypair = feeRecipient
+    innerFeeRecipient: anchor.web3.Keypair = feeRecipient,
+    referrer: PublicKey | null = null
   ) => {
     const userTokenAccount = PublicKey.findProgramAddressSync(
         [
@@ -434,7 +500,7 @@ const ata = Keypair.generate()
       ))
     }
     let ix = await program.methods
-      .buy(new BN(tokenAmount.toString()), new BN(maxSolAmount.toString()))
+      .buy(new BN(tokenAmount.toString()), new BN(maxSolAmount.toString()), null)
       .accounts({
         user: user.publicKey,
         mint: mint,
@@ -446,8 +512,25 @@ const ata = Keypair.generate()
         systemProgram: anchor.web3.SystemProgram.programId,
         feeRecipient: feeRecipient.publicKey,
         program: program.programId,bondingCurveTokenAccount,
+        referrer: referrer,
+        referrerStats: referrer
+          ? pk.findProgramAddressSync(
+              [Buffer.from("referrer-stats"), referrer.toBuffer()],
+              program.programId
+            )[0]
+          : null,
+        userDeposit: null,
+        quoteMint: null,
+        quoteVault: null,
+        userQuoteAccount: null,
+        feeRecipientQuoteAccount: null,
+        creatorQuoteAccount: null,
+        referrerQuoteAccount: null,
+        quoteTokenProgram: null,
+        launchConfig: null,
         tokenProgram: TOKEN_2022_PROGRAM_ID
       })
+      .remainingAccounts(transferHookAccounts(bondingCurvePDA))
       .instruction();
 
 preixs.push(ix)
@@ -492,8 +575,20 @@ await provider.sendAndConfirm(tx)
         // @ts-ignore
         feeRecipient: feeRecipient.publicKey,
         program: program.programId,
-        tokenProgram: TOKEN_2022_PROGRAM_ID,bondingCurveTokenAccount
+        tokenProgram: TOKEN_2022_PROGRAM_ID,bondingCurveTokenAccount,
+        referrer: null,
+        referrerStats: null,
+        userDeposit: null,
+        quoteMint: null,
+        quoteVault: null,
+        userQuoteAccount: null,
+        feeRecipientQuoteAccount: null,
+        creatorQuoteAccount: null,
+        referrerQuoteAccount: null,
+        quoteTokenProgram: null,
+        launchConfig: null,
       })
+      .remainingAccounts(transferHookAccounts(user.publicKey))
       .instruction();
       
 const tx = new Transaction().add(ix)
@@ -510,6 +605,671 @@ await provider.sendAndConfirm(tx)
     };
   };
 
+  // updates the global params, keeping the defaults the tests run with for anything not given
+  const setGlobalParams = async (overrides: {
+    creatorFeeShareBasisPoints?: BN;
+    referralFeeShareBasisPoints?: BN;
+    launchParamsLimits?: any;
+    maxTransferFee?: any;
+    maxLaunchFeeBasisPoints?: BN;
+  } = {}) => {
+    await program.methods
+      .setParams(
+        feeRecipient.publicKey,
+        withdrawAuthority.publicKey,
+        new BN(DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE.toString()),
+        new BN(DEFAULT_INITIAL_VIRTUAL_SOL_RESERVE.toString()),
+        new BN(DEFAULT_INITIAL_TOKEN_RESERVES.toString()),
+        new BN(DEFAULT_TOKEN_BALANCE.toString()),
+        new BN(DEFAULT_FEE_BASIS_POINTS.toString()),
+        overrides.creatorFeeShareBasisPoints ?? new BN(0),
+        overrides.referralFeeShareBasisPoints ?? new BN(0),
+        NO_LAUNCH_FEE_SCHEDULE,
+        { tokenExhaustion: {} },
+        overrides.launchParamsLimits ?? NO_LAUNCH_PARAMS_LIMITS,
+        overrides.maxTransferFee ?? NO_TRANSFER_FEE,
+        overrides.maxLaunchFeeBasisPoints ?? new BN(0)
+      )
+      .accounts({
+        user: authority.publicKey,
+        program: program.programId,
+      })
+      .signers([authority])
+      .rpc();
+  };
+
+  // accounts the transfer hook of `curveMint` needs for transfers out of `owner`'s token account
+  const transferHookAccountsFor = (curveMint: PublicKey, owner: PublicKey): AccountMeta[] => [
+    {
+      pubkey: pk.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), curveMint.toBuffer()],
+        program.programId
+      )[0],
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: pk.findProgramAddressSync(
+        [Buffer.from("user"), owner.toBuffer(), curveMint.toBuffer()],
+        program.programId
+      )[0],
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: pk.findProgramAddressSync(
+        [Buffer.from(BONDING_CURVE_SEED), curveMint.toBuffer()],
+        program.programId
+      )[0],
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: program.programId, isSigner: false, isWritable: false },
+  ];
+
+  type Curve = {
+    mint: PublicKey;
+    bondingCurve: PublicKey;
+    bondingCurveTokenAccount: PublicKey;
+    transferHook: boolean;
+    quoteMint: PublicKey | null;
+    quoteTokenProgram: PublicKey;
+    launchConfig: PublicKey | null;
+  };
+
+  // launch config at `index` with the default launch params, as amounts of `quoteMint` for curves priced in one
+  const setCurveLaunchConfig = async (
+    index: number,
+    options: { quoteMint?: PublicKey; graduationTarget?: any } = {}
+  ) => {
+    const indexBuffer = Buffer.alloc(2);
+    indexBuffer.writeUInt16LE(index);
+    const [launchConfig] = pk.findProgramAddressSync(
+      [Buffer.from("launch-config"), indexBuffer],
+      program.programId
+    );
+
+    await program.methods
+      .setLaunchConfig(
+        index,
+        feeRecipient.publicKey,
+        new BN(DEFAULT_FEE_BASIS_POINTS.toString()),
+        {
+          initialVirtualTokenReserves: new BN(DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE.toString()),
+          initialVirtualSolReserves: new BN(DEFAULT_INITIAL_VIRTUAL_SOL_RESERVE.toString()),
+          initialRealTokenReserves: new BN(DEFAULT_INITIAL_TOKEN_RESERVES.toString()),
+          initialTokenSupply: new BN(DEFAULT_TOKEN_BALANCE.toString()),
+        },
+        NO_LAUNCH_FEE_SCHEDULE,
+        options.quoteMint ?? PublicKey.default,
+        options.graduationTarget ?? { tokenExhaustion: {} }
+      )
+      .accountsPartial({
+        launchConfig,
+        user: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+
+    return launchConfig;
+  };
+
+  // quote mint accounts of a trade on `curve`, all null on curves priced in SOL
+  const curveQuoteAccounts = (curve: Curve, user: PublicKey) => {
+    if (!curve.quoteMint) {
+      return {
+        quoteMint: null,
+        quoteVault: null,
+        userQuoteAccount: null,
+        feeRecipientQuoteAccount: null,
+        creatorQuoteAccount: null,
+        referrerQuoteAccount: null,
+        quoteTokenProgram: null,
+      };
+    }
+    const quoteAccount = (owner: PublicKey) =>
+      getAssociatedTokenAddressSync(curve.quoteMint, owner, true, curve.quoteTokenProgram);
+    return {
+      quoteMint: curve.quoteMint,
+      quoteVault: quoteAccount(curve.bondingCurve),
+      userQuoteAccount: quoteAccount(user),
+      feeRecipientQuoteAccount: quoteAccount(feeRecipient.publicKey),
+      creatorQuoteAccount: quoteAccount(tokenCreator.publicKey),
+      referrerQuoteAccount: null,
+      quoteTokenProgram: curve.quoteTokenProgram,
+    };
+  };
+
+  // transfer hook accounts for transfers out of `owner`'s token account, none for mints without the hook
+  const curveHookAccounts = (curve: Curve, owner: PublicKey) =>
+    curve.transferHook ? transferHookAccountsFor(curve.mint, owner) : [];
+
+  // creates a curve of its own, for tests that can't share the one the other tests run on
+  const createCurve = async (options: {
+    rateLimitPolicy?: any;
+    vestingParams?: any;
+    initialBuy?: any;
+    launchFeeSchedule?: any;
+    maxWalletBasisPoints?: BN;
+    presale?: any;
+    softCap?: any;
+    transferFee?: any;
+    transferHook?: boolean;
+    quoteMint?: PublicKey;
+    quoteTokenProgram?: PublicKey;
+    launchConfig?: PublicKey;
+  } = {}): Promise<Curve> => {
+    const quoteTokenProgram = options.quoteTokenProgram ?? TOKEN_PROGRAM_ID;
+    const transferHook = options.transferHook ?? false;
+    const mintKeypair = Keypair.generate();
+    const curveMint = mintKeypair.publicKey;
+    const [curveBondingCurve] = pk.findProgramAddressSync(
+      [Buffer.from(BONDING_CURVE_SEED), curveMint.toBuffer()],
+      program.programId
+    );
+    const curveBondingCurveTokenAccount = getAssociatedTokenAddressSync(
+      curveMint,
+      curveBondingCurve,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const [vestingPDA] = pk.findProgramAddressSync(
+      [Buffer.from("vesting"), curveMint.toBuffer()],
+      program.programId
+    );
+
+    const metadata: TokenMetadata = {
+      mint: curveMint,
+      name: "test",
+      symbol: "tst",
+      uri: "https://pastebin.com/raw/Me8ibY8S",
+      additionalMetadata: [],
+    };
+    const mintLen = getMintLen(
+      transferHook
+        ? [ExtensionType.TransferFeeConfig, ExtensionType.MetadataPointer, ExtensionType.TransferHook]
+        : [ExtensionType.TransferFeeConfig, ExtensionType.MetadataPointer]
+    );
+    const metadataLen = TYPE_SIZE + LENGTH_SIZE + pack(metadata).length;
+    const mintLamports = await connection.getMinimumBalanceForRentExemption(mintLen + metadataLen);
+
+    await program.methods
+      .create(
+        metadata.name,
+        metadata.symbol,
+        metadata.uri,
+        { blue: {} },
+        { constantProduct: {} },
+        options.rateLimitPolicy ?? NO_RATE_LIMIT_POLICY,
+        options.vestingParams ?? null,
+        options.initialBuy ?? null,
+        options.launchFeeSchedule ?? null,
+        options.maxWalletBasisPoints ?? new BN(0),
+        options.presale ?? null,
+        options.softCap ?? null,
+        null,
+        options.transferFee ?? NO_TRANSFER_FEE,
+        [],
+        transferHook
+      )
+      .accountsPartial({
+        mint: curveMint,
+        creator: tokenCreator.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        bondingCurveTokenAccount: curveBondingCurveTokenAccount,
+        vesting: options.vestingParams ? vestingPDA : null,
+        vestingTokenAccount: options.vestingParams
+          ? getAssociatedTokenAddressSync(curveMint, vestingPDA, true, TOKEN_2022_PROGRAM_ID)
+          : null,
+        feeRecipient: options.initialBuy ? feeRecipient.publicKey : null,
+        creatorTokenAccount: options.initialBuy
+          ? getAssociatedTokenAddressSync(curveMint, tokenCreator.publicKey, false, TOKEN_2022_PROGRAM_ID)
+          : null,
+        creatorDeposit: options.initialBuy && options.softCap
+          ? pk.findProgramAddressSync(
+              [Buffer.from("user-deposit"), curveMint.toBuffer(), tokenCreator.publicKey.toBuffer()],
+              program.programId
+            )[0]
+          : null,
+        quoteMint: options.quoteMint ?? null,
+        quoteVault: options.quoteMint
+          ? getAssociatedTokenAddressSync(options.quoteMint, curveBondingCurve, true, quoteTokenProgram)
+          : null,
+        quoteTokenProgram: options.quoteMint ? quoteTokenProgram : null,
+        creatorQuoteAccount: null,
+        feeRecipientQuoteAccount: null,
+        launchConfig: options.launchConfig ?? null,
+      })
+      .remainingAccounts(
+        options.initialBuy && transferHook ? transferHookAccountsFor(curveMint, curveBondingCurve) : []
+      )
+      .preInstructions([
+        SystemProgram.createAccount({
+          fromPubkey: tokenCreator.publicKey,
+          newAccountPubkey: curveMint,
+          space: mintLen,
+          lamports: mintLamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+      ])
+      .signers([mintKeypair])
+      .rpc();
+
+    return {
+      mint: curveMint,
+      bondingCurve: curveBondingCurve,
+      bondingCurveTokenAccount: curveBondingCurveTokenAccount,
+      transferHook,
+      quoteMint: options.quoteMint ?? null,
+      quoteTokenProgram,
+      launchConfig: options.launchConfig ?? null,
+    };
+  };
+
+  // buys on a curve created with `createCurve`, creating the user's token account if needed
+  const curveBuy = async (
+    curve: Curve,
+    user: anchor.web3.Keypair,
+    tokenAmount: bigint,
+    maxSolAmount: bigint,
+    options: { presaleProof?: any; userDeposit?: boolean } = {}
+  ) => {
+    const userTokenAccount = getAssociatedTokenAddressSync(
+      curve.mint,
+      user.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const preInstructions = (await connection.getAccountInfo(userTokenAccount))
+      ? []
+      : [
+          createAssociatedTokenAccountInstruction(
+            user.publicKey,
+            userTokenAccount,
+            user.publicKey,
+            curve.mint,
+            TOKEN_2022_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID
+          ),
+        ];
+
+    const tx = await program.methods
+      .buy(new BN(tokenAmount.toString()), new BN(maxSolAmount.toString()), options.presaleProof ?? null)
+      .accountsPartial({
+        user: user.publicKey,
+        mint: curve.mint,
+        userTokenAccount,
+        bondingCurveTokenAccount: curve.bondingCurveTokenAccount,
+        feeRecipient: feeRecipient.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        referrer: null,
+        referrerStats: null,
+        userDeposit: options.userDeposit
+          ? pk.findProgramAddressSync(
+              [Buffer.from("user-deposit"), curve.mint.toBuffer(), user.publicKey.toBuffer()],
+              program.programId
+            )[0]
+          : null,
+        ...curveQuoteAccounts(curve, user.publicKey),
+        launchConfig: curve.launchConfig,
+      })
+      .remainingAccounts(curveHookAccounts(curve, curve.bondingCurve))
+      .preInstructions(preInstructions)
+      .transaction();
+
+    return await sendTransaction(program, tx, [user], user.publicKey);
+  };
+
+  // sells on a curve created with `createCurve`
+  const curveSell = async (
+    curve: Curve,
+    user: anchor.web3.Keypair,
+    tokenAmount: bigint,
+    minSolAmount: bigint,
+    options: { userDeposit?: boolean } = {}
+  ) => {
+    const tx = await program.methods
+      .sell(new BN(tokenAmount.toString()), new BN(minSolAmount.toString()))
+      .accountsPartial({
+        user: user.publicKey,
+        mint: curve.mint,
+        userTokenAccount: getAssociatedTokenAddressSync(
+          curve.mint,
+          user.publicKey,
+          false,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        bondingCurveTokenAccount: curve.bondingCurveTokenAccount,
+        feeRecipient: feeRecipient.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        referrer: null,
+        referrerStats: null,
+        userDeposit: options.userDeposit
+          ? pk.findProgramAddressSync(
+              [Buffer.from("user-deposit"), curve.mint.toBuffer(), user.publicKey.toBuffer()],
+              program.programId
+            )[0]
+          : null,
+        ...curveQuoteAccounts(curve, user.publicKey),
+        launchConfig: curve.launchConfig,
+      })
+      .remainingAccounts(curveHookAccounts(curve, user.publicKey))
+      .transaction();
+
+    return await sendTransaction(program, tx, [user], user.publicKey);
+  };
+
+  // wallet to wallet transfer of a curve's token, creating the destination token account if needed
+  const curveTransfer = async (curve: Curve, from: anchor.web3.Keypair, to: PublicKey, amount: bigint) => {
+    const source = getAssociatedTokenAddressSync(curve.mint, from.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    const destination = getAssociatedTokenAddressSync(curve.mint, to, false, TOKEN_2022_PROGRAM_ID);
+
+    const transferInstruction = createTransferCheckedInstruction(
+      source,
+      curve.mint,
+      destination,
+      from.publicKey,
+      amount,
+      Number(DEFAULT_DECIMALS),
+      [],
+      TOKEN_2022_PROGRAM_ID
+    );
+    transferInstruction.keys.push(...curveHookAccounts(curve, from.publicKey));
+
+    const tx = new Transaction().add(
+      createAssociatedTokenAccountIdempotentInstruction(
+        from.publicKey,
+        destination,
+        to,
+        curve.mint,
+        TOKEN_2022_PROGRAM_ID,
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      ),
+      transferInstruction
+    );
+    return await sendTransaction(program, tx, [from], from.publicKey);
+  };
+
+  // presale allowlist leaf of `wallet`, hashed like `presale_leaf`
+  const presaleLeaf = (wallet: PublicKey, maxTokenAmount: bigint) => {
+    const amount = Buffer.alloc(8);
+    amount.writeBigUInt64LE(maxTokenAmount);
+    return Buffer.from(keccak_256(Buffer.concat([wallet.toBuffer(), amount])));
+  };
+
+  // parent of two allowlist nodes, hashed in sorted order like `verify_merkle_proof`
+  const presaleNode = (a: Buffer, b: Buffer) =>
+    Buffer.from(keccak_256(Buffer.compare(a, b) <= 0 ? Buffer.concat([a, b]) : Buffer.concat([b, a])));
+
+  // refunds tokens bought on a soft-cap curve that missed its cap
+  const curveRefund = async (curve: Curve, user: anchor.web3.Keypair, tokenAmount: bigint) => {
+    await program.methods
+      .refund(new BN(tokenAmount.toString()))
+      .accountsPartial({
+        user: user.publicKey,
+        mint: curve.mint,
+        bondingCurve: curve.bondingCurve,
+        bondingCurveTokenAccount: curve.bondingCurveTokenAccount,
+        userTokenAccount: getAssociatedTokenAddressSync(curve.mint, user.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        userDeposit: pk.findProgramAddressSync(
+          [Buffer.from("user-deposit"), curve.mint.toBuffer(), user.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        quoteMint: null,
+        quoteVault: null,
+        userQuoteAccount: null,
+        quoteTokenProgram: null,
+      })
+      .remainingAccounts(curveHookAccounts(curve, user.publicKey))
+      .signers([user])
+      .rpc();
+  };
+
+  // freezes or unfreezes the curve of `curveMint` as the guardian
+  const setCurveFrozen = async (curveMint: PublicKey, frozen: boolean) => {
+    await program.methods
+      .setFrozen(frozen)
+      .accounts({
+        user: authority.publicKey,
+        mint: curveMint,
+        program: program.programId,
+      })
+      .signers([authority])
+      .rpc();
+  };
+
+  // withdraws what is left in a completed curve to the withdraw authority
+  const curveWithdraw = async (curve: Curve) => {
+    const tx = await program.methods
+      .withdraw()
+      .accountsPartial({
+        user: withdrawAuthority.publicKey,
+        mint: curve.mint,
+        bondingCurve: curve.bondingCurve,
+        bondingCurveTokenAccount: curve.bondingCurveTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        quoteMint: null,
+        quoteVault: null,
+        userQuoteAccount: null,
+        quoteTokenProgram: null,
+      })
+      .remainingAccounts(curveHookAccounts(curve, curve.bondingCurve))
+      .transaction();
+
+    return await sendTransaction(program, tx, [withdrawAuthority], withdrawAuthority.publicKey);
+  };
+
+  // claims the vested creator allocation of a curve created with `vestingParams`
+  const curveClaimVested = async (curve: Curve) => {
+    const [vestingPDA] = pk.findProgramAddressSync(
+      [Buffer.from("vesting"), curve.mint.toBuffer()],
+      program.programId
+    );
+    const creatorTokenAccount = getAssociatedTokenAddressSync(
+      curve.mint,
+      tokenCreator.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .claimVested()
+      .accountsPartial({
+        creator: tokenCreator.publicKey,
+        mint: curve.mint,
+        vesting: vestingPDA,
+        vestingTokenAccount: getAssociatedTokenAddressSync(curve.mint, vestingPDA, true, TOKEN_2022_PROGRAM_ID),
+        creatorTokenAccount: creatorTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(curveHookAccounts(curve, vestingPDA))
+      .preInstructions([
+        createAssociatedTokenAccountIdempotentInstruction(
+          tokenCreator.publicKey,
+          creatorTokenAccount,
+          tokenCreator.publicKey,
+          curve.mint,
+          TOKEN_2022_PROGRAM_ID,
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        ),
+      ])
+      .signers([tokenCreator])
+      .rpc();
+
+    return creatorTokenAccount;
+  };
+
+  // buys out the real token reserves of a curve
+  const completeCurve = async (curve: Curve) => {
+    const bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    const amm = ammFromBondingCurve(bondingCurveAccount, DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE);
+    const tokenAmount = amm.realTokenReserves;
+    const solAmount = amm.getBuyPrice(tokenAmount);
+    await curveBuy(
+      curve,
+      tokenCreator,
+      tokenAmount,
+      solAmount + calculateFee(solAmount, Number(DEFAULT_FEE_BASIS_POINTS))
+    );
+  };
+
+  // whirlpool accounts of a curve's pool against its quote mint, wrapped SOL for curves priced in SOL
+  const whirlpoolAccounts = (curve: Curve) => {
+    const quoteMint = curve.quoteMint ?? NATIVE_MINT;
+    const mintIsA = Buffer.compare(curve.mint.toBuffer(), quoteMint.toBuffer()) < 0;
+    const [tokenMintA, tokenMintB] = mintIsA ? [curve.mint, quoteMint] : [quoteMint, curve.mint];
+    const tickSpacing = Buffer.alloc(2);
+    tickSpacing.writeUInt16LE(WHIRLPOOL_TICK_SPACING);
+    const [whirlpool] = pk.findProgramAddressSync(
+      [Buffer.from("whirlpool"), WHIRLPOOLS_CONFIG.toBuffer(), tokenMintA.toBuffer(), tokenMintB.toBuffer(), tickSpacing],
+      WHIRLPOOL_PROGRAM_ID
+    );
+    const tokenBadge = (tokenMint: PublicKey) =>
+      pk.findProgramAddressSync(
+        [Buffer.from("token_badge"), WHIRLPOOLS_CONFIG.toBuffer(), tokenMint.toBuffer()],
+        WHIRLPOOL_PROGRAM_ID
+      )[0];
+    const tickArray = (tickIndex: number) => {
+      const ticksInArray = TICK_ARRAY_SIZE * WHIRLPOOL_TICK_SPACING;
+      const startTickIndex = Math.floor(tickIndex / ticksInArray) * ticksInArray;
+      return pk.findProgramAddressSync(
+        [Buffer.from("tick_array"), whirlpool.toBuffer(), Buffer.from(startTickIndex.toString())],
+        WHIRLPOOL_PROGRAM_ID
+      )[0];
+    };
+    const tickUpperIndex = Math.floor(MAX_TICK_INDEX / WHIRLPOOL_TICK_SPACING) * WHIRLPOOL_TICK_SPACING;
+
+    return {
+      mintIsA,
+      tokenMintA,
+      tokenMintB,
+      whirlpool,
+      tokenBadgeA: tokenBadge(tokenMintA),
+      tokenBadgeB: tokenBadge(tokenMintB),
+      feeTier: pk.findProgramAddressSync(
+        [Buffer.from("fee_tier"), WHIRLPOOLS_CONFIG.toBuffer(), tickSpacing],
+        WHIRLPOOL_PROGRAM_ID
+      )[0],
+      tickArrayLower: tickArray(-tickUpperIndex),
+      tickArrayUpper: tickArray(tickUpperIndex),
+    };
+  };
+
+  // sqrt price, as a Q64.64, of a constant product curve's spot price in the whirlpool's mint order
+  const curveSqrtPrice = (bondingCurveAccount: any, mintIsA: boolean) => {
+    const virtualSolReserves = BigInt(bondingCurveAccount.virtualSolReserves.toString());
+    const virtualTokenReserves = BigInt(bondingCurveAccount.virtualTokenReserves.toString());
+    const priceX128 = mintIsA
+      ? (virtualSolReserves << 128n) / virtualTokenReserves
+      : (virtualTokenReserves << 128n) / virtualSolReserves;
+    let root = priceX128;
+    let next = (root + 1n) >> 1n;
+    while (next < root) {
+      root = next;
+      next = (root + priceX128 / root) >> 1n;
+    }
+    return root;
+  };
+
+  // creates a curve's whirlpool directly, the way anyone could before the curve migrates
+  const initializeWhirlpool = async (curve: Curve, sqrtPrice: bigint) => {
+    const accounts = whirlpoolAccounts(curve);
+    const tokenVaultA = Keypair.generate();
+    const tokenVaultB = Keypair.generate();
+    const tokenProgram = (tokenMint: PublicKey) =>
+      tokenMint.equals(curve.mint) ? TOKEN_2022_PROGRAM_ID : curve.quoteTokenProgram;
+
+    const data = Buffer.alloc(8 + 2 + 16);
+    require("crypto")
+      .createHash("sha256")
+      .update("global:initialize_pool_v2")
+      .digest()
+      .copy(data, 0, 0, 8);
+    data.writeUInt16LE(WHIRLPOOL_TICK_SPACING, 8);
+    data.writeBigUInt64LE(sqrtPrice & ((1n << 64n) - 1n), 10);
+    data.writeBigUInt64LE(sqrtPrice >> 64n, 18);
+
+    const ix = new anchor.web3.TransactionInstruction({
+      programId: WHIRLPOOL_PROGRAM_ID,
+      keys: [
+        { pubkey: WHIRLPOOLS_CONFIG, isSigner: false, isWritable: false },
+        { pubkey: accounts.tokenMintA, isSigner: false, isWritable: false },
+        { pubkey: accounts.tokenMintB, isSigner: false, isWritable: false },
+        { pubkey: accounts.tokenBadgeA, isSigner: false, isWritable: false },
+        { pubkey: accounts.tokenBadgeB, isSigner: false, isWritable: false },
+        { pubkey: tokenCreator.publicKey, isSigner: true, isWritable: true },
+        { pubkey: accounts.whirlpool, isSigner: false, isWritable: true },
+        { pubkey: tokenVaultA.publicKey, isSigner: true, isWritable: true },
+        { pubkey: tokenVaultB.publicKey, isSigner: true, isWritable: true },
+        { pubkey: accounts.feeTier, isSigner: false, isWritable: false },
+        { pubkey: tokenProgram(accounts.tokenMintA), isSigner: false, isWritable: false },
+        { pubkey: tokenProgram(accounts.tokenMintB), isSigner: false, isWritable: false },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: anchor.web3.SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+      ],
+      data,
+    });
+    await provider.sendAndConfirm(new Transaction().add(ix), [tokenVaultA, tokenVaultB]);
+
+    return { tokenVaultA: tokenVaultA.publicKey, tokenVaultB: tokenVaultB.publicKey };
+  };
+
+  // migrates a completed curve into its whirlpool, into the given vaults if the pool already exists
+  const migrateCurve = async (
+    curve: Curve,
+    existingVaults: { tokenVaultA: PublicKey; tokenVaultB: PublicKey } | null = null
+  ) => {
+    const accounts = whirlpoolAccounts(curve);
+    const tokenVaultA = Keypair.generate();
+    const tokenVaultB = Keypair.generate();
+    const positionMint = Keypair.generate();
+
+    const tx = await program.methods
+      .migrateToWhirlpool()
+      .accountsPartial({
+        payer: tokenCreator.publicKey,
+        mint: curve.mint,
+        bondingCurveTokenAccount: curve.bondingCurveTokenAccount,
+        quoteMint: curve.quoteMint ?? NATIVE_MINT,
+        whirlpoolsConfig: WHIRLPOOLS_CONFIG,
+        feeTier: accounts.feeTier,
+        whirlpool: accounts.whirlpool,
+        tokenBadgeA: accounts.tokenBadgeA,
+        tokenBadgeB: accounts.tokenBadgeB,
+        tokenVaultA: existingVaults?.tokenVaultA ?? tokenVaultA.publicKey,
+        tokenVaultB: existingVaults?.tokenVaultB ?? tokenVaultB.publicKey,
+        tickArrayLower: accounts.tickArrayLower,
+        tickArrayUpper: accounts.tickArrayUpper,
+        position: pk.findProgramAddressSync(
+          [Buffer.from("position"), positionMint.publicKey.toBuffer()],
+          WHIRLPOOL_PROGRAM_ID
+        )[0],
+        positionMint: positionMint.publicKey,
+        positionTokenAccount: getAssociatedTokenAddressSync(
+          positionMint.publicKey,
+          curve.bondingCurve,
+          true,
+          TOKEN_PROGRAM_ID
+        ),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        quoteTokenProgram: curve.quoteTokenProgram,
+        positionTokenProgram: TOKEN_PROGRAM_ID,
+        memoProgram: MEMO_PROGRAM_ID,
+        whirlpoolProgram: WHIRLPOOL_PROGRAM_ID,
+      })
+      .remainingAccounts(curveHookAccounts(curve, curve.bondingCurve))
+      .preInstructions([anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+      .transaction();
+
+    return await sendTransaction(
+      program,
+      tx,
+      existingVaults ? [tokenCreator, positionMint] : [tokenCreator, tokenVaultA, tokenVaultB, positionMint],
+      tokenCreator.publicKey
+    );
+  };
+
   before(async () => {
   });
 
@@ -529,11 +1289,20 @@ await provider.sendAndConfirm(tx)
 
     await program.methods
       .setParams(
+        feeRecipient.publicKey,
+        withdrawAuthority.publicKey,
         new BN(DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE.toString()),
         new BN(DEFAULT_INITIAL_VIRTUAL_SOL_RESERVE.toString()),
         new BN(DEFAULT_INITIAL_TOKEN_RESERVES.toString()),
         new BN(DEFAULT_TOKEN_BALANCE.toString()),
-        new BN(DEFAULT_FEE_BASIS_POINTS.toString())
+        new BN(DEFAULT_FEE_BASIS_POINTS.toString()),
+        new BN(0),
+        new BN(0),
+        NO_LAUNCH_FEE_SCHEDULE,
+        { tokenExhaustion: {} },
+        NO_LAUNCH_PARAMS_LIMITS,
+        NO_TRANSFER_FEE,
+        new BN(0)
       )
       .accounts({
         user: authority.publicKey,
@@ -565,7 +1334,7 @@ await provider.sendAndConfirm(tx)
     uri,
     additionalMetadata: [],
   };
-  const mintLen = getMintLen([ExtensionType.TransferFeeConfig, ExtensionType.MetadataPointer]);
+  const mintLen = getMintLen([ExtensionType.TransferFeeConfig, ExtensionType.MetadataPointer, ExtensionType.TransferHook]);
 
   const metadataLen = TYPE_SIZE+ LENGTH_SIZE + pack(metadata).length;
 console.log(mintLen+metadataLen)
@@ -573,13 +1342,28 @@ console.log(mintLen+metadataLen)
     
   console.log(mintLen+ metadataLen);
     const tx = await program.methods
-      .create(name, symbol, uri,  { blue: {} })
+      .create(name, symbol, uri,  { blue: {} }, { constantProduct: {} }, {
+        creatorLimitBasisPoints: new BN(50),
+        userLimitBasisPoints: new BN(0),
+        windowSeconds: new BN(3600),
+      }, null, null, null, new BN(0), null, null, null, NO_TRANSFER_FEE, [], true)
       .accounts({
         mint: mint,
         creator: tokenCreator.publicKey,
         program: program.programId,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
-        bondingCurveTokenAccount
+        bondingCurveTokenAccount,
+        vesting: null,
+        vestingTokenAccount: null,
+        feeRecipient: null,
+        creatorTokenAccount: null,
+        creatorDeposit: null,
+        quoteMint: null,
+        quoteVault: null,
+        quoteTokenProgram: null,
+        creatorQuoteAccount: null,
+        feeRecipientQuoteAccount: null,
+        launchConfig: null,
       })
       .preInstructions([
         SystemProgram.createAccount({
@@ -704,6 +1488,12 @@ console.log(mintLen+metadataLen)
         tradeEvent.solAmount.toString(),
         (buyMaxSOLAmount - fee).toString()
       );
+
+      //no launch fee schedule, so the base fee applies
+      assert.equal(
+        tradeEvent.feeBasisPoints.toString(),
+        DEFAULT_FEE_BASIS_POINTS.toString()
+      );
     }
 
     const tokenAmount = await connection.getTokenAccountBalance(
@@ -754,6 +1544,11 @@ console.log(mintLen+metadataLen)
       feeRecipient.publicKey
     );
 
+    let curvePreSaleSOLBalance = await connection.getBalance(bondingCurvePDA);
+    let curvePreSaleAccount = await program.account.bondingCurve.fetch(
+      bondingCurvePDA
+    );
+
     let txResult = await simpleSell(tokenCreator, tokenAmount, minSolAmount, feeRecipient);
 
     let feeRecipientPostBuySOLBalance = await connection.getBalance(
@@ -807,6 +1602,122 @@ console.log(mintLen+metadataLen)
       bondingCurvePDA
     );
     assertBondingCurve(currentAMM, bondingCurveAccount);
+
+    //the fees are paid out of the sale, so the curve only loses what left its reserves
+    let curvePostSaleSOLBalance = await connection.getBalance(bondingCurvePDA);
+    assert.equal(
+      (curvePreSaleSOLBalance - curvePostSaleSOLBalance).toString(),
+      curvePreSaleAccount.realSolReserves
+        .sub(bondingCurveAccount.realSolReserves)
+        .toString()
+    );
+  });
+
+  it("can quote a buy and a sell", async () => {
+    let currentAMM = await getAmmFromBondingCurve();
+
+    let tokenAmount = 1000n;
+    let buyPrice = currentAMM.getBuyPrice(tokenAmount);
+
+    let buyQuote = await program.methods
+      .quoteBuy(new BN(tokenAmount.toString()))
+      .accounts({
+        mint: mint,
+        bondingCurveTokenAccount,
+        launchConfig: null,
+      })
+      .view();
+
+    assert.equal(buyQuote.tokenAmount.toString(), tokenAmount.toString());
+    assert.equal(buyQuote.solAmount.toString(), buyPrice.toString());
+    assert.equal(
+      buyQuote.fee.toString(),
+      calculateFee(buyPrice, Number(DEFAULT_FEE_BASIS_POINTS)).toString()
+    );
+
+    let sellQuote = await program.methods
+      .quoteSell(new BN(tokenAmount.toString()))
+      .accounts({
+        mint: mint,
+        bondingCurveTokenAccount,
+        launchConfig: null,
+      })
+      .view();
+
+    let sellResult = currentAMM.applySell(tokenAmount);
+    assert.equal(sellQuote.solAmount.toString(), sellResult.sol_amount.toString());
+    assert.equal(
+      sellQuote.virtualSolReserves.toString(),
+      currentAMM.virtualSolReserves.toString()
+    );
+    assert.equal(
+      sellQuote.realSolReserves.toString(),
+      currentAMM.realSolReserves.toString()
+    );
+  });
+
+  it("can accrue and claim referral fees", async () => {
+    const referralFeeShareBasisPoints = 1000n;
+    await setGlobalParams({
+      referralFeeShareBasisPoints: new BN(referralFeeShareBasisPoints.toString()),
+    });
+
+    //the referrer starts out unfunded, its fees accrue in its stats
+    const referrer = anchor.web3.Keypair.generate();
+    const [referrerStatsPDA] = pk.findProgramAddressSync(
+      [Buffer.from("referrer-stats"), referrer.publicKey.toBuffer()],
+      program.programId
+    );
+
+    let currentAMM = await getAmmFromBondingCurve();
+    let tokenAmount = 100_000_000_000n;
+    let buyPrice = currentAMM.getBuyPrice(tokenAmount);
+    let fee = calculateFee(buyPrice, Number(DEFAULT_FEE_BASIS_POINTS));
+    let referralFee = calculateFee(fee, Number(referralFeeShareBasisPoints));
+
+    await simpleBuy(tokenCreator, tokenAmount, buyPrice + fee, feeRecipient, referrer.publicKey);
+
+    let referrerStats = await program.account.referrerStats.fetch(referrerStatsPDA);
+    assert.equal(referrerStats.referrer.toBase58(), referrer.publicKey.toBase58());
+    assert.equal(referrerStats.totalEarned.toString(), referralFee.toString());
+    assert.equal(await connection.getBalance(referrer.publicKey), 0);
+
+    //funded so the claimed fees don't leave it below rent exempt
+    await fundAccountSOL(connection, referrer.publicKey, LAMPORTS_PER_SOL);
+    let referrerPreClaimBalance = await connection.getBalance(referrer.publicKey);
+
+    await program.methods
+      .claimReferralFees()
+      .accounts({
+        referrer: referrer.publicKey,
+      })
+      .signers([referrer])
+      .rpc();
+
+    let referrerPostClaimBalance = await connection.getBalance(referrer.publicKey);
+    assert.equal(
+      referrerPostClaimBalance - referrerPreClaimBalance,
+      Number(referralFee)
+    );
+
+    let errorCode = "";
+    try {
+      await program.methods
+        .claimReferralFees()
+        .accounts({
+          referrer: referrer.publicKey,
+        })
+        .signers([referrer])
+        .rpc();
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "NoReferralFeesToClaim");
+
+    await setGlobalParams();
   });
 
   //excpetion unit tests
@@ -820,8 +1731,13 @@ console.log(mintLen+metadataLen)
           user: withdrawAuthority.publicKey,
           mint: mint,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
-          bondingCurveTokenAccount
+          bondingCurveTokenAccount,
+          quoteMint: null,
+          quoteVault: null,
+          userQuoteAccount: null,
+          quoteTokenProgram: null,
         })
+        .remainingAccounts(transferHookAccounts(bondingCurvePDA))
         .transaction();
 
         await provider.sendAndConfirm(tx)
@@ -892,20 +1808,65 @@ console.log(mintLen+metadataLen)
     assert.equal(errorCode, "MinBuy");
   });
 
-  it("can't sell a token, not enough tokens", async () => {
+  it("can't buy a token while paused", async () => {
+    const setPaused = async (paused: boolean) => {
+      await program.methods
+        .setPaused(paused)
+        .accounts({
+          user: authority.publicKey,
+          program: program.programId,
+        })
+        .signers([authority])
+        .rpc();
+    };
+
+    await setPaused(true);
+
     let errorCode = "";
     try {
-      await simpleSell(tokenCreator, DEFAULT_TOKEN_BALANCE, 0n, feeRecipient);
+      await simpleBuy(tokenCreator, 1000n, 1_000_000_000n, feeRecipient);
     } catch (err) {
       let anchorError = getAnchorError(err);
       if (anchorError) {
         errorCode = anchorError.error.errorCode.code;
       }
     }
-    assert.equal(errorCode, "InsufficientTokens");
+    assert.equal(errorCode, "ProgramPaused");
+
+    await setPaused(false);
   });
 
-  it("can't sell 0 tokens", async () => {
+  it("can't sell a token while frozen", async () => {
+    await setCurveFrozen(mint, true);
+
+    let errorCode = "";
+    try {
+      await simpleSell(tokenCreator, 1000n, 0n, feeRecipient);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "BondingCurveFrozen");
+
+    await setCurveFrozen(mint, false);
+  });
+
+  it("can't sell a token, not enough tokens", async () => {
+    let errorCode = "";
+    try {
+      await simpleSell(tokenCreator, DEFAULT_TOKEN_BALANCE, 0n, feeRecipient);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InsufficientTokens");
+  });
+
+  it("can't sell 0 tokens", async () => {
     let errorCode = "";
     try {
       await simpleSell(tokenCreator, 0n, 0n, feeRecipient);
@@ -1039,8 +2000,13 @@ console.log(mintLen+metadataLen)
           user: tokenCreator.publicKey,
           mint: mint,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
-          bondingCurveTokenAccount
+          bondingCurveTokenAccount,
+          quoteMint: null,
+          quoteVault: null,
+          userQuoteAccount: null,
+          quoteTokenProgram: null,
         })
+        .remainingAccounts(transferHookAccounts(bondingCurvePDA))
         .transaction();
 
         await provider.sendAndConfirm(tx)
@@ -1061,6 +2027,9 @@ console.log(mintLen+metadataLen)
 
   //it can withdraw
   it("can withdraw", async () => {
+    //the curve isn't migrated in these tests, freezing it marks it as one that can't be
+    await setCurveFrozen(mint, true);
+
     let withdrawAuthorityPreSOLBalance = await connection.getBalance(
       feeRecipient.publicKey
     );
@@ -1081,8 +2050,13 @@ console.log(mintLen+metadataLen)
         user: withdrawAuthority.publicKey,
         mint: mint,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
-        bondingCurveTokenAccount
+        bondingCurveTokenAccount,
+        quoteMint: null,
+        quoteVault: null,
+        userQuoteAccount: null,
+        quoteTokenProgram: null,
       })
+      .remainingAccounts(transferHookAccounts(bondingCurvePDA))
       .transaction();
 
       await provider.sendAndConfirm(tx)
@@ -1141,15 +2115,960 @@ console.log(mintLen+metadataLen)
     assert.notEqual(bondingCurveAccount, null);
   });
 
+  it("rate limits withdrawals per curve", async () => {
+    //another curve was just withdrawn, each curve has its own cooldown
+    const curve = await createCurve();
+    await completeCurve(curve);
+    await setCurveFrozen(curve.mint, true);
+    await curveWithdraw(curve);
+
+    let errorCode = "";
+    try {
+      await curveWithdraw(curve);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "WithdrawCooldownNotMet");
+  });
+
+  it("can't claim creator fees as non-creator", async () => {
+    let errorCode = "";
+    try {
+      await program.methods
+        .claimCreatorFees()
+        .accounts({
+          creator: withdrawAuthority.publicKey,
+          mint: mint,
+          creatorVaultTokenAccount: null,
+          creatorTokenAccount: null,
+          tokenProgram: null,
+          program: program.programId,
+        })
+        .signers([withdrawAuthority])
+        .rpc();
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InvalidCreator");
+  });
+
+  it("rate limits wallets that only received tokens until the curve completes", async () => {
+    const userLimitBasisPoints = 100n;
+    const curve = await createCurve({
+      transferHook: true,
+      rateLimitPolicy: {
+        creatorLimitBasisPoints: new BN(0),
+        userLimitBasisPoints: new BN(userLimitBasisPoints.toString()),
+        windowSeconds: new BN(3600),
+      },
+    });
+    const cap = (DEFAULT_TOKEN_BALANCE * userLimitBasisPoints) / 10000n;
+
+    //the creator isn't limited, it hands out more than a user may move per window
+    let bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    let amm = ammFromBondingCurve(bondingCurveAccount, DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE);
+    let solAmount = amm.getBuyPrice(cap * 2n);
+    await curveBuy(
+      curve,
+      tokenCreator,
+      cap * 2n,
+      solAmount + calculateFee(solAmount, Number(DEFAULT_FEE_BASIS_POINTS))
+    );
+
+    const receiver = Keypair.generate();
+    await fundAccountSOL(connection, receiver.publicKey, LAMPORTS_PER_SOL);
+    await curveTransfer(curve, tokenCreator, receiver.publicKey, cap * 2n);
+
+    //the receiver never traded against the curve, so it has no transfer data yet
+    const [receiverTransferData] = pk.findProgramAddressSync(
+      [Buffer.from("user"), receiver.publicKey.toBuffer(), curve.mint.toBuffer()],
+      program.programId
+    );
+    assert.equal(await connection.getAccountInfo(receiverTransferData), null);
+
+    //without transfer data the bucket can't be saved, so the transfer is refused
+    let errorCode = "";
+    try {
+      await curveTransfer(curve, receiver, Keypair.generate().publicKey, cap / 2n);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "UserTransferDataNotInitialized");
+
+    await program.methods
+      .initUserTransferData()
+      .accountsPartial({
+        payer: receiver.publicKey,
+        user: receiver.publicKey,
+        mint: curve.mint,
+        userTransferData: receiverTransferData,
+      })
+      .signers([receiver])
+      .rpc();
+
+    //the bucket is saved between transfers, so two transfers together can't go over the cap
+    await curveTransfer(curve, receiver, Keypair.generate().publicKey, cap / 2n + 1n);
+
+    errorCode = "";
+    try {
+      await curveTransfer(curve, receiver, Keypair.generate().publicKey, cap / 2n + 1n);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "TransferLimitExceeded");
+
+    await completeCurve(curve);
+    await curveTransfer(curve, receiver, Keypair.generate().publicKey, cap + 1n);
+  });
+
+  it("can't run the transfer hook outside a transfer", async () => {
+    const curve = await createCurve({
+      transferHook: true,
+      rateLimitPolicy: {
+        creatorLimitBasisPoints: new BN(100),
+        userLimitBasisPoints: new BN(100),
+        windowSeconds: new BN(3600),
+      },
+    });
+    const tokenAmount = 1_000_000n * 10n ** DEFAULT_DECIMALS;
+    await curveBuy(curve, tokenCreator, tokenAmount, BigInt(LAMPORTS_PER_SOL));
+
+    //a direct call would drain the creator's bucket without moving any tokens
+    const creatorTokenAccount = getAssociatedTokenAddressSync(curve.mint, tokenCreator.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    let errorCode = "";
+    try {
+      await program.methods
+        .transferHook(new BN(tokenAmount.toString()))
+        .accountsPartial({
+          sourceToken: creatorTokenAccount,
+          mint: curve.mint,
+          destinationToken: creatorTokenAccount,
+          owner: tokenCreator.publicKey,
+          extraAccountMetaList: pk.findProgramAddressSync(
+            [Buffer.from("extra-account-metas"), curve.mint.toBuffer()],
+            program.programId
+          )[0],
+          userTransferData: pk.findProgramAddressSync(
+            [Buffer.from("user"), tokenCreator.publicKey.toBuffer(), curve.mint.toBuffer()],
+            program.programId
+          )[0],
+          bondingCurve: curve.bondingCurve,
+        })
+        .rpc();
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "NotTransferring");
+  });
+
+  it("can create a curve with an initial buy", async () => {
+    const tokenAmount = 10_000_000n * 10n ** DEFAULT_DECIMALS;
+    const amm = new AMM(
+      DEFAULT_INITIAL_VIRTUAL_SOL_RESERVE,
+      DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE,
+      0n,
+      DEFAULT_INITIAL_TOKEN_RESERVES,
+      DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE
+    );
+    const solAmount = amm.getBuyPrice(tokenAmount);
+    const fee = calculateFee(solAmount, Number(DEFAULT_FEE_BASIS_POINTS));
+
+    const curve = await createCurve({
+      initialBuy: {
+        tokenAmount: new BN(tokenAmount.toString()),
+        maxSolCost: new BN((solAmount + fee).toString()),
+      },
+    });
+
+    const creatorTokenAccount = getAssociatedTokenAddressSync(
+      curve.mint,
+      tokenCreator.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const tokenBalance = await connection.getTokenAccountBalance(creatorTokenAccount);
+    assert.equal(tokenBalance.value.amount, tokenAmount.toString());
+
+    const bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(bondingCurveAccount.realTokenReserves.toString(), (DEFAULT_INITIAL_TOKEN_RESERVES - tokenAmount).toString());
+    assert.equal(bondingCurveAccount.realSolReserves.toString(), solAmount.toString());
+  });
+
+  it("records the initial buy of a soft-cap curve for refunds", async () => {
+    const tokenAmount = 10_000_000n * 10n ** DEFAULT_DECIMALS;
+    const curve = await createCurve({
+      initialBuy: {
+        tokenAmount: new BN(tokenAmount.toString()),
+        maxSolCost: new BN(LAMPORTS_PER_SOL),
+      },
+      softCap: {
+        minRealSolReserves: new BN(100 * LAMPORTS_PER_SOL),
+        deadline: new BN(Math.floor(Date.now() / 1000) + 3600),
+      },
+    });
+
+    const bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    const userDepositAccount = await program.account.userDeposit.fetch(
+      pk.findProgramAddressSync(
+        [Buffer.from("user-deposit"), curve.mint.toBuffer(), tokenCreator.publicKey.toBuffer()],
+        program.programId
+      )[0]
+    );
+    assert.equal(userDepositAccount.tokenAmount.toString(), tokenAmount.toString());
+    assert.equal(userDepositAccount.solAmount.toString(), bondingCurveAccount.realSolReserves.toString());
+  });
+
+  it("can't create a curve with an initial buy over the max wallet", async () => {
+    let errorCode = "";
+    try {
+      await createCurve({
+        maxWalletBasisPoints: new BN(100),
+        initialBuy: {
+          tokenAmount: new BN(((DEFAULT_TOKEN_BALANCE * 200n) / 10000n).toString()),
+          maxSolCost: new BN(100 * LAMPORTS_PER_SOL),
+        },
+      });
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "MaxWalletExceeded");
+  });
+
+  it("can claim vested tokens once the schedule ends", async () => {
+    const vestingAmount = 1_000_000n * 10n ** DEFAULT_DECIMALS;
+    const curve = await createCurve({
+      vestingParams: {
+        amount: new BN(vestingAmount.toString()),
+        cliffSeconds: new BN(0),
+        durationSeconds: new BN(1),
+      },
+    });
+
+    let vestingAccount = await program.account.vesting.fetch(
+      pk.findProgramAddressSync([Buffer.from("vesting"), curve.mint.toBuffer()], program.programId)[0]
+    );
+    assert.equal(vestingAccount.totalAmount.toString(), vestingAmount.toString());
+    assert.equal(vestingAccount.claimedAmount.toString(), "0");
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const creatorTokenAccount = await curveClaimVested(curve);
+    const tokenAmount = await connection.getTokenAccountBalance(creatorTokenAccount);
+    assert.equal(tokenAmount.value.amount, vestingAmount.toString());
+
+    vestingAccount = await program.account.vesting.fetch(
+      pk.findProgramAddressSync([Buffer.from("vesting"), curve.mint.toBuffer()], program.programId)[0]
+    );
+    assert.equal(vestingAccount.claimedAmount.toString(), vestingAmount.toString());
+
+    //everything is claimed already
+    let errorCode = "";
+    try {
+      await curveClaimVested(curve);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "NoVestedTokensToClaim");
+  });
+
+  it("can't claim vested tokens before the cliff", async () => {
+    const curve = await createCurve({
+      vestingParams: {
+        amount: new BN((1_000_000n * 10n ** DEFAULT_DECIMALS).toString()),
+        cliffSeconds: new BN(3600),
+        durationSeconds: new BN(7200),
+      },
+    });
+
+    let errorCode = "";
+    try {
+      await curveClaimVested(curve);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "NoVestedTokensToClaim");
+  });
+
+  it("caps the launch fee a creator may set", async () => {
+    const maxLaunchFeeBasisPoints = 1000n;
+    await setGlobalParams({ maxLaunchFeeBasisPoints: new BN(maxLaunchFeeBasisPoints.toString()) });
+
+    let errorCode = "";
+    try {
+      await createCurve({
+        launchFeeSchedule: {
+          ...NO_LAUNCH_FEE_SCHEDULE,
+          initialFeeBasisPoints: new BN((maxLaunchFeeBasisPoints + 1n).toString()),
+          duration: new BN(60),
+        },
+      });
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InvalidLaunchFeeSchedule");
+
+    const curve = await createCurve({
+      launchFeeSchedule: {
+        ...NO_LAUNCH_FEE_SCHEDULE,
+        initialFeeBasisPoints: new BN(maxLaunchFeeBasisPoints.toString()),
+        duration: new BN(60),
+      },
+    });
+    const bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(
+      bondingCurveAccount.launchFeeSchedule.initialFeeBasisPoints.toString(),
+      maxLaunchFeeBasisPoints.toString()
+    );
+
+    await setGlobalParams();
+  });
+
+  it("can't buy past the max wallet", async () => {
+    const maxWalletBasisPoints = 100n;
+    const curve = await createCurve({ maxWalletBasisPoints: new BN(maxWalletBasisPoints.toString()) });
+    const maxWalletAmount = (DEFAULT_TOKEN_BALANCE * maxWalletBasisPoints) / 10000n;
+
+    const buyer = Keypair.generate();
+    await fundAccountSOL(connection, buyer.publicKey, 10 * LAMPORTS_PER_SOL);
+
+    //up to the cap is fine
+    await curveBuy(curve, buyer, maxWalletAmount, BigInt(5 * LAMPORTS_PER_SOL));
+
+    let errorCode = "";
+    try {
+      await curveBuy(curve, buyer, 1n * 10n ** DEFAULT_DECIMALS, BigInt(LAMPORTS_PER_SOL));
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "MaxWalletExceeded");
+  });
+
+  it("charges the rate limit for the tokens a buy clamped at graduation got", async () => {
+    const graduationSolReserves = BigInt(LAMPORTS_PER_SOL);
+    const launchConfig = await setCurveLaunchConfig(3, {
+      graduationTarget: { realSolReserves: { 0: new BN(graduationSolReserves.toString()) } },
+    });
+    const userLimitBasisPoints = 500n;
+    const curve = await createCurve({
+      launchConfig,
+      rateLimitPolicy: {
+        creatorLimitBasisPoints: new BN(0),
+        userLimitBasisPoints: new BN(userLimitBasisPoints.toString()),
+        windowSeconds: new BN(3600),
+      },
+    });
+    const cap = (DEFAULT_TOKEN_BALANCE * userLimitBasisPoints) / 10000n;
+
+    //the buy asks for twice the cap, but graduation clamps it below the cap
+    const trader = Keypair.generate();
+    await fundAccountSOL(connection, trader.publicKey, 5 * LAMPORTS_PER_SOL);
+    await curveBuy(curve, trader, cap * 2n, 2n * graduationSolReserves);
+
+    const bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(bondingCurveAccount.complete, true);
+    const boughtTokenAmount = DEFAULT_INITIAL_TOKEN_RESERVES - BigInt(bondingCurveAccount.realTokenReserves.toString());
+    assert.isTrue(boughtTokenAmount < cap);
+
+    const userTransferData = await program.account.userTransferData.fetch(
+      pk.findProgramAddressSync(
+        [Buffer.from("user"), trader.publicKey.toBuffer(), curve.mint.toBuffer()],
+        program.programId
+      )[0]
+    );
+    assert.equal(userTransferData.availableAmount.toString(), (cap - boughtTokenAmount).toString());
+  });
+
+  it("can't create a curve with an initial buy ahead of its presale", async () => {
+    let errorCode = "";
+    try {
+      await createCurve({
+        presale: {
+          merkleRoot: Array.from(presaleLeaf(tokenCreator.publicKey, 1n)),
+          endTimestamp: new BN(Math.floor(Date.now() / 1000) + 3600),
+        },
+        initialBuy: {
+          tokenAmount: new BN((1_000_000n * 10n ** DEFAULT_DECIMALS).toString()),
+          maxSolCost: new BN(LAMPORTS_PER_SOL),
+        },
+      });
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InvalidPresale");
+  });
+
+  it("only lets allowlisted wallets buy during the presale, up to their cap", async () => {
+    const allowlisted = Keypair.generate();
+    const other = Keypair.generate();
+    await fundAccountSOL(connection, allowlisted.publicKey, 10 * LAMPORTS_PER_SOL);
+    await fundAccountSOL(connection, other.publicKey, 10 * LAMPORTS_PER_SOL);
+
+    const maxTokenAmount = 5_000_000n * 10n ** DEFAULT_DECIMALS;
+    const allowlistedLeaf = presaleLeaf(allowlisted.publicKey, maxTokenAmount);
+    const otherLeaf = presaleLeaf(other.publicKey, maxTokenAmount);
+    const curve = await createCurve({
+      presale: {
+        merkleRoot: Array.from(presaleNode(allowlistedLeaf, otherLeaf)),
+        endTimestamp: new BN(Math.floor(Date.now() / 1000) + 3600),
+      },
+    });
+
+    const expectError = async (buy: Promise<any>, code: string) => {
+      let errorCode = "";
+      try {
+        await buy;
+      } catch (err) {
+        let anchorError = getAnchorError(err);
+        if (anchorError) {
+          errorCode = anchorError.error.errorCode.code;
+        }
+      }
+      assert.equal(errorCode, code);
+    };
+
+    //no proof, or a proof of another wallet
+    await expectError(curveBuy(curve, tokenCreator, maxTokenAmount, BigInt(LAMPORTS_PER_SOL)), "NotAllowlisted");
+    await expectError(
+      curveBuy(curve, tokenCreator, maxTokenAmount, BigInt(LAMPORTS_PER_SOL), {
+        presaleProof: { maxTokenAmount: new BN(maxTokenAmount.toString()), proof: [Array.from(otherLeaf)] },
+      }),
+      "NotAllowlisted"
+    );
+
+    const presaleProof = {
+      maxTokenAmount: new BN(maxTokenAmount.toString()),
+      proof: [Array.from(otherLeaf)],
+    };
+    await curveBuy(curve, allowlisted, maxTokenAmount, BigInt(LAMPORTS_PER_SOL), { presaleProof });
+
+    const tokenBalance = await connection.getTokenAccountBalance(
+      getAssociatedTokenAddressSync(curve.mint, allowlisted.publicKey, false, TOKEN_2022_PROGRAM_ID)
+    );
+    assert.equal(tokenBalance.value.amount, maxTokenAmount.toString());
+
+    //the cap is used up
+    await expectError(
+      curveBuy(curve, allowlisted, 1n, BigInt(LAMPORTS_PER_SOL), { presaleProof }),
+      "PresaleLimitExceeded"
+    );
+  });
+
+  it("refunds depositors pro-rata once a soft-cap curve misses its cap", async () => {
+    const deadline = Math.floor(Date.now() / 1000) + 10;
+    const curve = await createCurve({
+      softCap: {
+        minRealSolReserves: new BN(100 * LAMPORTS_PER_SOL),
+        deadline: new BN(deadline),
+      },
+    });
+
+    const early = Keypair.generate();
+    const late = Keypair.generate();
+    await fundAccountSOL(connection, early.publicKey, 10 * LAMPORTS_PER_SOL);
+    await fundAccountSOL(connection, late.publicKey, 10 * LAMPORTS_PER_SOL);
+
+    const tokenAmount = 10_000_000n * 10n ** DEFAULT_DECIMALS;
+    await curveBuy(curve, early, tokenAmount, BigInt(LAMPORTS_PER_SOL), { userDeposit: true });
+    await curveBuy(curve, late, tokenAmount, BigInt(LAMPORTS_PER_SOL), { userDeposit: true });
+
+    //the early buyer sells at a profit, leaving less on the curve than the late buyer deposited
+    await curveSell(curve, early, tokenAmount, 0n, { userDeposit: true });
+
+    const lateDeposit = await program.account.userDeposit.fetch(
+      pk.findProgramAddressSync(
+        [Buffer.from("user-deposit"), curve.mint.toBuffer(), late.publicKey.toBuffer()],
+        program.programId
+      )[0]
+    );
+    let bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    const realSolReserves = BigInt(bondingCurveAccount.realSolReserves.toString());
+    assert.isTrue(realSolReserves < BigInt(lateDeposit.solAmount.toString()));
+
+    //refunds only open after the deadline
+    let errorCode = "";
+    try {
+      await curveRefund(curve, late, tokenAmount);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "BondingCurveNotFailed");
+
+    while (Math.floor(Date.now() / 1000) <= deadline + 1) {
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+    }
+
+    //the last depositor gets everything that is left, not more
+    const lateStartingLamports = BigInt(await connection.getBalance(late.publicKey));
+    await curveRefund(curve, late, tokenAmount);
+    const lateEndingLamports = BigInt(await connection.getBalance(late.publicKey));
+    bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal((lateEndingLamports - lateStartingLamports).toString(), realSolReserves.toString());
+    assert.equal(bondingCurveAccount.realSolReserves.toString(), "0");
+    assert.isTrue(bondingCurveAccount.failed);
+  });
+
+  it("can't open a deposit on a curve without a soft cap", async () => {
+    const curve = await createCurve();
+
+    const user = Keypair.generate();
+    await fundAccountSOL(connection, user.publicKey, 10 * LAMPORTS_PER_SOL);
+
+    let errorCode = "";
+    try {
+      await curveBuy(curve, user, 10_000_000n * 10n ** DEFAULT_DECIMALS, BigInt(LAMPORTS_PER_SOL), { userDeposit: true });
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "UnexpectedUserDeposit");
+  });
+
+  it("can buy and sell on a curve priced in a quote mint", async () => {
+    const quoteMint = await createMint(connection, tokenCreator, tokenCreator.publicKey, null, 6);
+    const trader = Keypair.generate();
+    await fundAccountSOL(connection, trader.publicKey, LAMPORTS_PER_SOL);
+
+    const quoteAccounts = {};
+    for (const owner of [trader.publicKey, tokenCreator.publicKey, feeRecipient.publicKey]) {
+      quoteAccounts[owner.toBase58()] = (
+        await getOrCreateAssociatedTokenAccount(connection, tokenCreator, quoteMint, owner)
+      ).address;
+    }
+    const traderQuoteAccount = quoteAccounts[trader.publicKey.toBase58()];
+    await mintTo(connection, tokenCreator, quoteMint, traderQuoteAccount, tokenCreator, 1_000_000_000_000n);
+
+    //quote amounts depend on the mint, so the curve launches under a launch config priced in it
+    let errorCode = "";
+    try {
+      await createCurve({ quoteMint });
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InvalidLaunchConfig");
+
+    const launchConfig = await setCurveLaunchConfig(1, { quoteMint });
+    const curve = await createCurve({ quoteMint, launchConfig });
+    const quoteVault = getAssociatedTokenAddressSync(quoteMint, curve.bondingCurve, true, TOKEN_PROGRAM_ID);
+    const quoteBalance = async (account: PublicKey) =>
+      BigInt((await connection.getTokenAccountBalance(account)).value.amount);
+
+    //the buy is paid in the quote mint, the cost goes to the vault and the fee on top
+    const tokenAmount = 10_000_000n * 10n ** DEFAULT_DECIMALS;
+    let bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    const cost = ammFromBondingCurve(bondingCurveAccount, DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE).getBuyPrice(tokenAmount);
+    const buyFee = calculateFee(cost, Number(DEFAULT_FEE_BASIS_POINTS));
+
+    const traderStartingBalance = await quoteBalance(traderQuoteAccount);
+    await curveBuy(curve, trader, tokenAmount, cost + buyFee);
+    assert.equal((traderStartingBalance - (await quoteBalance(traderQuoteAccount))).toString(), (cost + buyFee).toString());
+    assert.equal((await quoteBalance(quoteVault)).toString(), cost.toString());
+
+    //the sell pays the trader net of the fee
+    bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    const realSolReservesBeforeSell = BigInt(bondingCurveAccount.realSolReserves.toString());
+    const traderBalanceBeforeSell = await quoteBalance(traderQuoteAccount);
+    await curveSell(curve, trader, tokenAmount, 0n);
+
+    bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    const sellAmount = realSolReservesBeforeSell - BigInt(bondingCurveAccount.realSolReserves.toString());
+    const sellFee = calculateFee(sellAmount, Number(DEFAULT_FEE_BASIS_POINTS));
+    assert.equal(
+      ((await quoteBalance(traderQuoteAccount)) - traderBalanceBeforeSell).toString(),
+      (sellAmount - sellFee).toString()
+    );
+    assert.equal((await quoteBalance(quoteVault)).toString(), bondingCurveAccount.realSolReserves.toString());
+  });
+
+  it("can't create a curve priced in a quote mint with a transfer fee", async () => {
+    const quoteMintKeypair = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: provider.wallet.publicKey,
+          newAccountPubkey: quoteMintKeypair.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          quoteMintKeypair.publicKey,
+          tokenCreator.publicKey,
+          tokenCreator.publicKey,
+          100,
+          1_000_000n,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(quoteMintKeypair.publicKey, 6, tokenCreator.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [quoteMintKeypair]
+    );
+
+    let errorCode = "";
+    try {
+      await createCurve({ quoteMint: quoteMintKeypair.publicKey, quoteTokenProgram: TOKEN_2022_PROGRAM_ID });
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "QuoteMintNotSupported");
+  });
+
+  it("trades and harvests a curve with a transfer fee", async () => {
+    const transferFee = {
+      transferFeeBasisPoints: 100,
+      maximumFee: new BN((10n ** 18n).toString()),
+    };
+    await setGlobalParams({ maxTransferFee: transferFee });
+
+    const curve = await createCurve({ transferFee });
+    const trader = Keypair.generate();
+    await fundAccountSOL(connection, trader.publicKey, 10 * LAMPORTS_PER_SOL);
+    const traderTokenAccount = getAssociatedTokenAddressSync(curve.mint, trader.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    const withheldFee = (amount: bigint) => (amount * BigInt(transferFee.transferFeeBasisPoints)) / 10000n;
+
+    //the trader receives the tokens net of the transfer fee
+    const tokenAmount = 10_000_000n * 10n ** DEFAULT_DECIMALS;
+    await curveBuy(curve, trader, tokenAmount, BigInt(10 * LAMPORTS_PER_SOL));
+    const receivedTokenAmount = BigInt((await connection.getTokenAccountBalance(traderTokenAccount)).value.amount);
+    assert.equal(receivedTokenAmount.toString(), (tokenAmount - withheldFee(tokenAmount)).toString());
+
+    //the curve is credited with the tokens net of the transfer fee
+    let bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    const realTokenReservesBeforeSell = BigInt(bondingCurveAccount.realTokenReserves.toString());
+    await curveSell(curve, trader, receivedTokenAmount, 0n);
+    bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(
+      (BigInt(bondingCurveAccount.realTokenReserves.toString()) - realTokenReservesBeforeSell).toString(),
+      (receivedTokenAmount - withheldFee(receivedTokenAmount)).toString()
+    );
+
+    //the withheld fees are harvested into the mint and withdrawn to the creator vault
+    await program.methods
+      .harvestWithheld()
+      .accountsPartial({
+        mint: curve.mint,
+        bondingCurve: curve.bondingCurve,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        [traderTokenAccount, curve.bondingCurveTokenAccount].map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .rpc();
+
+    const [creatorVault] = pk.findProgramAddressSync(
+      [Buffer.from("creator-vault"), curve.bondingCurve.toBuffer()],
+      program.programId
+    );
+    const creatorVaultTokenAccount = getAssociatedTokenAddressSync(curve.mint, creatorVault, true, TOKEN_2022_PROGRAM_ID);
+    await program.methods
+      .withdrawWithheld()
+      .accountsPartial({
+        payer: provider.wallet.publicKey,
+        mint: curve.mint,
+        bondingCurve: curve.bondingCurve,
+        creatorVault,
+        creatorVaultTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const creatorVaultTokenBalance = await connection.getTokenAccountBalance(creatorVaultTokenAccount);
+    assert.equal(
+      creatorVaultTokenBalance.value.amount,
+      (withheldFee(tokenAmount) + withheldFee(receivedTokenAmount)).toString()
+    );
+
+    await setGlobalParams();
+  });
+
+  it("can't sell more tokens than the curve sold", async () => {
+    const vestingAmount = 1_000_000n * 10n ** DEFAULT_DECIMALS;
+    const curve = await createCurve({
+      vestingParams: {
+        amount: new BN(vestingAmount.toString()),
+        cliffSeconds: new BN(0),
+        durationSeconds: new BN(1),
+      },
+    });
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await curveClaimVested(curve);
+
+    //the vested tokens never came from the curve, so nothing was sold to buy them back
+    let errorCode = "";
+    try {
+      await curveSell(curve, tokenCreator, vestingAmount, 0n);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InsufficientSOL");
+  });
+
+  it("can't sell an amount the transfer fee takes whole", async () => {
+    const transferFee = {
+      transferFeeBasisPoints: 100,
+      maximumFee: new BN((10n ** 18n).toString()),
+    };
+    await setGlobalParams({ maxTransferFee: transferFee });
+
+    const curve = await createCurve({ transferFee });
+    const trader = Keypair.generate();
+    await fundAccountSOL(connection, trader.publicKey, LAMPORTS_PER_SOL);
+    await curveBuy(curve, trader, 1_000_000n * 10n ** DEFAULT_DECIMALS, BigInt(LAMPORTS_PER_SOL));
+
+    //the fee on a single base unit rounds up to the whole unit
+    let errorCode = "";
+    try {
+      await curveSell(curve, trader, 1n, 0n);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "MinSell");
+
+    await setGlobalParams();
+  });
+
+  //migration unit tests
+  it("can migrate a completed curve once", async () => {
+    const curve = await createCurve();
+
+    let errorCode = "";
+    try {
+      await migrateCurve(curve);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "BondingCurveNotComplete");
+
+    await completeCurve(curve);
+    let txResult = await migrateCurve(curve);
+
+    let migrateEvents = txResult.events.filter((event) => {
+      return event.name === "migrateEvent";
+    });
+    assert.equal(migrateEvents.length, 1);
+
+    let migrateEvent = toEvent("migrateEvent", migrateEvents[0]);
+    assert.notEqual(migrateEvent, null);
+    if (migrateEvent != null) {
+      assert.equal(migrateEvent.whirlpool.toBase58(), whirlpoolAccounts(curve).whirlpool.toBase58());
+      assert.isTrue(migrateEvent.liquidity.gtn(0));
+    }
+
+    let bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(bondingCurveAccount.migrated, true);
+
+    errorCode = "";
+    try {
+      await migrateCurve(curve);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "BondingCurveMigrated");
+  });
+
+  it("can migrate a completed curve priced in a quote mint", async () => {
+    const quoteMint = await createMint(connection, tokenCreator, tokenCreator.publicKey, null, 6);
+    for (const owner of [tokenCreator.publicKey, feeRecipient.publicKey]) {
+      await getOrCreateAssociatedTokenAccount(connection, tokenCreator, quoteMint, owner);
+    }
+    await mintTo(
+      connection,
+      tokenCreator,
+      quoteMint,
+      getAssociatedTokenAddressSync(quoteMint, tokenCreator.publicKey),
+      tokenCreator,
+      1_000_000_000_000n
+    );
+
+    const launchConfig = await setCurveLaunchConfig(2, { quoteMint });
+    const curve = await createCurve({ quoteMint, launchConfig });
+    await completeCurve(curve);
+
+    //the pool is paired against the quote mint, out of the quote vault
+    const txResult = await migrateCurve(curve);
+    const migrateEvents = txResult.events.filter((event) => {
+      return event.name === "migrateEvent";
+    });
+    assert.equal(migrateEvents.length, 1);
+
+    const migrateEvent = toEvent("migrateEvent", migrateEvents[0]);
+    assert.notEqual(migrateEvent, null);
+    if (migrateEvent != null) {
+      assert.equal(migrateEvent.whirlpool.toBase58(), whirlpoolAccounts(curve).whirlpool.toBase58());
+      assert.isTrue(migrateEvent.liquidity.gtn(0));
+    }
+
+    //whatever the pool did not take stays in the quote vault
+    const bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(bondingCurveAccount.migrated, true);
+    const quoteVaultBalance = await connection.getTokenAccountBalance(bondingCurveAccount.quoteVault);
+    assert.equal(quoteVaultBalance.value.amount, bondingCurveAccount.realSolReserves.toString());
+  });
+
+  it("only withdraws a curve once it migrated", async () => {
+    const curve = await createCurve();
+    await completeCurve(curve);
+
+    //the whirlpool gets the reserves first
+    let errorCode = "";
+    try {
+      await curveWithdraw(curve);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "BondingCurveNotMigrated");
+
+    await migrateCurve(curve);
+    await curveWithdraw(curve);
+
+    const bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(bondingCurveAccount.realSolReserves.toString(), "0");
+    const bondingCurveTokenBalance = await connection.getTokenAccountBalance(curve.bondingCurveTokenAccount);
+    assert.equal(bondingCurveTokenBalance.value.amount, "0");
+  });
+
+  it("can't migrate a hooked mint without a token badge", async () => {
+    const curve = await createCurve({ transferHook: true });
+    await completeCurve(curve);
+
+    //the whirlpools config only pools transfer hook mints it issued a token badge for
+    let errorCode = "";
+    try {
+      await migrateCurve(curve);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "UnsupportedTokenMint");
+
+    let bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(bondingCurveAccount.migrated, false);
+  });
+
+  it("can migrate into a whirlpool created first at the curve price", async () => {
+    const curve = await createCurve();
+    await completeCurve(curve);
+
+    let bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    const vaults = await initializeWhirlpool(
+      curve,
+      curveSqrtPrice(bondingCurveAccount, whirlpoolAccounts(curve).mintIsA)
+    );
+
+    await migrateCurve(curve, vaults);
+
+    bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(bondingCurveAccount.migrated, true);
+  });
+
+  it("can't migrate into a whirlpool created first at another price", async () => {
+    const curve = await createCurve();
+    await completeCurve(curve);
+
+    let bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    const vaults = await initializeWhirlpool(
+      curve,
+      curveSqrtPrice(bondingCurveAccount, whirlpoolAccounts(curve).mintIsA) * 2n
+    );
+
+    let errorCode = "";
+    try {
+      await migrateCurve(curve, vaults);
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InvalidPoolPrice");
+
+    bondingCurveAccount = await program.account.bondingCurve.fetch(curve.bondingCurve);
+    assert.equal(bondingCurveAccount.migrated, false);
+  });
+
   //param unit tests
   it("can set params", async () => {
     let tx = await program.methods
       .setParams(
+        feeRecipient.publicKey,
+        withdrawAuthority.publicKey,
         new BN(DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE.toString()),
         new BN(DEFAULT_INITIAL_VIRTUAL_SOL_RESERVE.toString()),
         new BN(DEFAULT_INITIAL_TOKEN_RESERVES.toString()),
         new BN(DEFAULT_TOKEN_BALANCE.toString()),
-        new BN(DEFAULT_FEE_BASIS_POINTS.toString())
+        new BN(DEFAULT_FEE_BASIS_POINTS.toString()),
+        new BN(0),
+        new BN(0),
+        NO_LAUNCH_FEE_SCHEDULE,
+        { tokenExhaustion: {} },
+        NO_LAUNCH_PARAMS_LIMITS,
+        NO_TRANSFER_FEE,
+        new BN(0)
       )
       .accounts({
         user: authority.publicKey,
@@ -1238,12 +3157,20 @@ console.log(mintLen+metadataLen)
 
       await program.methods
         .setParams(
-          
+        randomFeeRecipient.publicKey,
+        randomWithdrawAuthority.publicKey,
         new BN(DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE.toString()),
         new BN(DEFAULT_INITIAL_VIRTUAL_SOL_RESERVE.toString()),
         new BN(DEFAULT_INITIAL_TOKEN_RESERVES.toString()),
         new BN(DEFAULT_TOKEN_BALANCE.toString()),
-        new BN(DEFAULT_FEE_BASIS_POINTS.toString())
+        new BN(DEFAULT_FEE_BASIS_POINTS.toString()),
+        new BN(0),
+        new BN(0),
+        NO_LAUNCH_FEE_SCHEDULE,
+        { tokenExhaustion: {} },
+        NO_LAUNCH_PARAMS_LIMITS,
+        NO_TRANSFER_FEE,
+        new BN(0)
         )
         .accounts({
           user: tokenCreator.publicKey,
@@ -1259,6 +3186,97 @@ console.log(mintLen+metadataLen)
     }
     assert.equal(errorCode, "InvalidAuthority");
   });
+
+  it("can't migrate a global that is already up to date", async () => {
+    let errorCode = "";
+    try {
+      await program.methods
+        .migrateGlobal()
+        .accounts({
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InvalidLegacyGlobal");
+  });
+
+  it("can't migrate user transfer data that is already up to date", async () => {
+    let errorCode = "";
+    try {
+      await program.methods
+        .migrateUserTransferData()
+        .accounts({
+          payer: tokenCreator.publicKey,
+          user: tokenCreator.publicKey,
+          mint: mint,
+        })
+        .signers([tokenCreator])
+        .rpc();
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InvalidLegacyUserTransferData");
+  });
+
+  it("can hand over authority", async () => {
+    const newAuthority = anchor.web3.Keypair.generate();
+
+    await program.methods
+      .proposeAuthority(newAuthority.publicKey)
+      .accounts({
+        user: authority.publicKey,
+        program: program.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    let global = await program.account.global.fetch(globalPDA);
+    assert.equal(global.authority.toBase58(), authority.publicKey.toBase58());
+    assert.equal(
+      global.pendingAuthority.toBase58(),
+      newAuthority.publicKey.toBase58()
+    );
+
+    let errorCode = "";
+    try {
+      await program.methods
+        .acceptAuthority()
+        .accounts({
+          user: tokenCreator.publicKey,
+          program: program.programId,
+        })
+        .signers([tokenCreator])
+        .rpc();
+    } catch (err) {
+      let anchorError = getAnchorError(err);
+      if (anchorError) {
+        errorCode = anchorError.error.errorCode.code;
+      }
+    }
+    assert.equal(errorCode, "InvalidPendingAuthority");
+
+    await program.methods
+      .acceptAuthority()
+      .accounts({
+        user: newAuthority.publicKey,
+        program: program.programId,
+      })
+      .signers([newAuthority])
+      .rpc();
+
+    global = await program.account.global.fetch(globalPDA);
+    assert.equal(global.authority.toBase58(), newAuthority.publicKey.toBase58());
+    assert.equal(global.pendingAuthority, null);
+  });
 });
 
 //TODO: Tests